@@ -1,4 +1,5 @@
-use sc_cli::{RunCmd, Subcommand};
+use std::path::PathBuf;
+use sc_cli::{ImportParams, RunCmd, SharedParams};
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -9,3 +10,64 @@ pub struct Cli {
 	#[structopt(flatten)]
 	pub run: RunCmd,
 }
+
+#[derive(Debug, StructOpt)]
+pub enum Subcommand {
+	#[structopt(flatten)]
+	Base(sc_cli::Subcommand),
+
+	/// Notarize files with the proof of existence pallet.
+	#[structopt(name = "poe")]
+	Poe(PoeCmd),
+}
+
+#[derive(Debug, StructOpt)]
+pub enum PoeCmd {
+	/// Print the claim key of a file.
+	#[structopt(name = "hash")]
+	Hash {
+		#[structopt(parse(from_os_str))]
+		file: PathBuf,
+	},
+
+	/// Look up the claim of a file at the best block of the local database.
+	#[structopt(name = "verify")]
+	Verify(PoeVerifyCmd),
+
+	/// Print a SCALE encoded `create_hashed_claim` call for a file, ready for signing.
+	#[structopt(name = "build-claim")]
+	BuildClaim {
+		#[structopt(parse(from_os_str))]
+		file: PathBuf,
+
+		/// Memo attached to the claim.
+		#[structopt(long, default_value = "")]
+		memo: String,
+
+		/// Number of blocks after which the claim expires.
+		#[structopt(long)]
+		lifetime: Option<u32>,
+	},
+}
+
+#[derive(Debug, StructOpt)]
+pub struct PoeVerifyCmd {
+	#[structopt(parse(from_os_str))]
+	pub file: PathBuf,
+
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl sc_cli::CliConfiguration for PoeVerifyCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
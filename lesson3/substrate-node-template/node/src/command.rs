@@ -16,9 +16,16 @@
 // limitations under the License.
 
 use crate::chain_spec;
-use crate::cli::Cli;
+use crate::cli::{Cli, PoeCmd, PoeVerifyCmd, Subcommand};
 use crate::service;
-use sc_cli::SubstrateCli;
+use codec::{Decode, Encode};
+use node_template_runtime::{opaque::Block, AccountId, BlockNumber, Runtime, RuntimeApi};
+use sc_cli::{Error, SubstrateCli};
+use sc_client_api::StorageProvider;
+use sc_service::Configuration;
+use sp_blockchain::HeaderBackend;
+use sp_core::{crypto::Ss58Codec, hexdisplay::HexDisplay, storage::StorageKey};
+use sp_runtime::generic::BlockId;
 
 impl SubstrateCli for Cli {
 	fn impl_name() -> &'static str {
@@ -65,10 +72,25 @@ pub fn run() -> sc_cli::Result<()> {
 	let cli = Cli::from_args();
 
 	match &cli.subcommand {
-		Some(subcommand) => {
+		Some(Subcommand::Base(subcommand)) => {
 			let runner = cli.create_runner(subcommand)?;
 			runner.run_subcommand(subcommand, |config| Ok(new_full_start!(config).0))
 		}
+		Some(Subcommand::Poe(PoeCmd::Hash { file })) => {
+			let hash = poe::document::hash_file(file)?;
+			println!("0x{}", HexDisplay::from(&hash.encode()));
+			Ok(())
+		}
+		Some(Subcommand::Poe(PoeCmd::Verify(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| verify_claim(cmd, config))
+		}
+		Some(Subcommand::Poe(PoeCmd::BuildClaim { file, memo, lifetime })) => {
+			let call = poe::document::build_claim_call::<Runtime, _>(file, memo.clone().into_bytes(), *lifetime)?;
+			let call = node_template_runtime::Call::PoeModule(call);
+			println!("0x{}", HexDisplay::from(&call.encode()));
+			Ok(())
+		}
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node(
@@ -79,3 +101,24 @@ pub fn run() -> sc_cli::Result<()> {
 		}
 	}
 }
+
+/// Print the `Proofs` entry of a file at the best block, failing if it is not claimed.
+fn verify_claim(cmd: &PoeVerifyCmd, config: Configuration) -> sc_cli::Result<()> {
+	let client = sc_service::new_full_client::<Block, RuntimeApi, service::Executor>(&config)?;
+	let best = client.info().best_hash;
+
+	let claim = poe::document::hash_file(&cmd.file)?.encode();
+	let key = StorageKey(poe::proof_storage_key::<Runtime>(&claim));
+	let data = client.storage(&BlockId::Hash(best), &key)
+		.map_err(|e| Error::Other(format!("{:?}", e)))?
+		.ok_or_else(|| Error::Input(format!("0x{} is not claimed at {}", HexDisplay::from(&claim), best)))?;
+	let claim_info = poe::ClaimInfo::<AccountId, BlockNumber, u64>::decode(&mut &data.0[..])
+		.map_err(|e| Error::Other(format!("invalid claim info: {:?}", e)))?;
+
+	println!("claim:      0x{}", HexDisplay::from(&claim));
+	println!("owner:      {}", claim_info.owner.to_ss58check());
+	println!("block:      {}", claim_info.block_number);
+	println!("created at: {}", claim_info.created_at);
+	println!("memo:       {}", String::from_utf8_lossy(&claim_info.memo));
+	Ok(())
+}
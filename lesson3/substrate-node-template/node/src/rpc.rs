@@ -0,0 +1,89 @@
+//! Node specific RPC methods.
+
+use std::{marker::PhantomData, sync::Arc};
+use codec::Encode;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use node_template_runtime::Runtime;
+use pallet_poe_certificate::{Certificate, CERTIFICATE_VERSION};
+use sc_client_api::{Backend, ProofProvider, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, storage::StorageKey};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+const RUNTIME_ERROR: i64 = 1;
+const CLAIM_NOT_FOUND: i64 = 2;
+
+/// Proof of existence RPC methods.
+#[rpc]
+pub trait PoeApi<BlockHash> {
+	/// Returns a portable certificate for `claim`, read at `at` or the last finalized block.
+	#[rpc(name = "poe_certificate")]
+	fn certificate(&self, claim: Bytes, at: Option<BlockHash>) -> Result<Certificate>;
+}
+
+/// Implements `PoeApi` on top of a full client.
+pub struct Poe<C, B> {
+	client: Arc<C>,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> Poe<C, B> {
+	/// Create a new `Poe` with the given client.
+	pub fn new(client: Arc<C>) -> Self {
+		Poe { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to read chain state.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, BE> PoeApi<Block::Hash> for Poe<C, (Block, BE)> where
+	Block: BlockT,
+	BE: Backend<Block> + 'static,
+	C: HeaderBackend<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + Send + Sync + 'static,
+{
+	fn certificate(&self, claim: Bytes, at: Option<Block::Hash>) -> Result<Certificate> {
+		let hash = at.unwrap_or_else(|| self.client.info().finalized_hash);
+		let id = BlockId::hash(hash);
+		let header = self.client.header(id)
+			.map_err(runtime_error)?
+			.ok_or_else(|| runtime_error(hash))?;
+
+		let key = poe::proof_storage_key::<Runtime>(&claim);
+		let claim_info = self.client.storage(&id, &StorageKey(key.clone()))
+			.map_err(runtime_error)?
+			.ok_or_else(|| RpcError {
+				code: ErrorCode::ServerError(CLAIM_NOT_FOUND),
+				message: "Claim not found.".into(),
+				data: None,
+			})?;
+		let proof = self.client.read_proof(&id, &mut std::iter::once(&key[..]))
+			.map_err(runtime_error)?;
+
+		Ok(Certificate {
+			version: CERTIFICATE_VERSION,
+			claim,
+			claim_info: Bytes(claim_info.0),
+			block_hash: Bytes(hash.encode()),
+			header: Bytes(header.encode()),
+			proof: proof.iter_nodes().map(Bytes).collect(),
+		})
+	}
+}
+
+/// Builds the RPC extensions of a full node.
+pub fn create_full<C, Block, BE>(client: Arc<C>) -> jsonrpc_core::IoHandler<sc_rpc::Metadata> where
+	Block: BlockT,
+	BE: Backend<Block> + 'static,
+	C: HeaderBackend<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + Send + Sync + 'static,
+{
+	let mut io = jsonrpc_core::IoHandler::default();
+	io.extend_with(PoeApi::to_delegate(Poe::<_, (Block, BE)>::new(client)));
+	io
+}
@@ -103,6 +103,7 @@ pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceEr
 			let provider = client as Arc<dyn StorageAndProofProvider<_, _>>;
 			Ok(Arc::new(GrandpaFinalityProofProvider::new(backend, provider)) as _)
 		})?
+		.with_rpc_extensions(|builder| Ok(crate::rpc::create_full(builder.client().clone())))?
 		.build()?;
 
 	if role.is_authority() {
@@ -0,0 +1,307 @@
+//! Portable proof of existence certificates.
+//!
+//! A certificate holds a `Proofs` entry together with the header of the block it was
+//! read at and a storage proof against that header's state root. It can be checked
+//! against a trusted finalized header without running a node.
+
+use std::fmt;
+use codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_runtime::traits::{Block as BlockT, HashFor, Header as HeaderT};
+use sp_state_machine::{read_proof_check, StorageProof};
+
+/// The certificate format version produced by this crate.
+pub const CERTIFICATE_VERSION: u32 = 1;
+
+/// A self-contained proof that a claim existed at a block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Certificate {
+	/// Format version, see `CERTIFICATE_VERSION`.
+	pub version: u32,
+	/// The claim, as used as the `Proofs` key.
+	pub claim: Bytes,
+	/// The SCALE encoded `ClaimInfo` stored for the claim.
+	pub claim_info: Bytes,
+	/// Hash of the block the claim was read at.
+	pub block_hash: Bytes,
+	/// The SCALE encoded header of that block.
+	pub header: Bytes,
+	/// Trie nodes proving the `Proofs` entry against the header state root.
+	pub proof: Vec<Bytes>,
+}
+
+/// Why a certificate was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+	/// The certificate was produced by an unknown format version.
+	UnsupportedVersion(u32),
+	/// The header or claim info cannot be decoded.
+	InvalidEncoding,
+	/// The header is not the trusted one, or does not match the block hash.
+	UntrustedHeader,
+	/// The storage proof does not match the header state root.
+	InvalidProof,
+	/// The proven `Proofs` entry differs from the certificate.
+	ClaimMismatch,
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::UnsupportedVersion(v) => write!(f, "unsupported certificate version {}", v),
+			Error::InvalidEncoding => write!(f, "invalid header or claim encoding"),
+			Error::UntrustedHeader => write!(f, "header does not match the trusted header"),
+			Error::InvalidProof => write!(f, "invalid storage proof"),
+			Error::ClaimMismatch => write!(f, "proven claim differs from the certificate"),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl Certificate {
+	/// Parse a JSON encoded certificate.
+	pub fn from_json(json: &str) -> serde_json::Result<Self> {
+		serde_json::from_str(json)
+	}
+
+	/// Encode the certificate as JSON.
+	pub fn to_json(&self) -> serde_json::Result<String> {
+		serde_json::to_string_pretty(self)
+	}
+
+	/// Check the certificate against a trusted header of a runtime `T` and return the decoded claim info.
+	pub fn verify<T, B>(&self, trusted_header: &B::Header) -> Result<poe::ClaimInfoOf<T>, Error> where
+		T: poe::Trait,
+		B: BlockT,
+	{
+		if self.version != CERTIFICATE_VERSION {
+			return Err(Error::UnsupportedVersion(self.version));
+		}
+
+		let header = B::Header::decode(&mut &self.header[..]).map_err(|_| Error::InvalidEncoding)?;
+		let hash = header.hash();
+		if hash != trusted_header.hash() || hash.encode() != self.block_hash.0 {
+			return Err(Error::UntrustedHeader);
+		}
+
+		let key = poe::proof_storage_key::<T>(&self.claim);
+		let proof = StorageProof::new(self.proof.iter().map(|node| node.0.clone()).collect());
+		let mut values = read_proof_check::<HashFor<B>, _>(*header.state_root(), proof, &[&key])
+			.map_err(|_| Error::InvalidProof)?;
+
+		match values.remove(&key) {
+			Some(Some(value)) if value == self.claim_info.0 => (),
+			_ => return Err(Error::ClaimMismatch),
+		}
+
+		poe::ClaimInfoOf::<T>::decode(&mut &self.claim_info[..]).map_err(|_| Error::InvalidEncoding)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+	use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+	use poe::ClaimInfo;
+	use sp_core::H256;
+	use sp_runtime::{
+		Perbill,
+		testing::{Block as TestBlock, ExtrinsicWrapper, Header},
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+	use sp_state_machine::{prove_read, InMemoryBackend};
+
+	type Block = TestBlock<ExtrinsicWrapper<u64>>;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+		pub const MinimumPeriod: u64 = 1;
+		pub const ExistentialDeposit: u64 = 1;
+		pub const MaxClaimLength: u32 = 6;
+		pub const MaxMemoLength: u32 = 8;
+		pub const ClaimDepositBase: u64 = 2;
+		pub const ClaimDepositPerByte: u64 = 1;
+		pub const RenewalFee: u64 = 5;
+		pub const MaxExpiriesPerBlock: u32 = 2;
+		pub const MaxAttestations: u32 = 2;
+		pub const DisputeBond: u64 = 10;
+		pub const MaxCoOwners: u32 = 3;
+		pub const ProposalLifetime: u64 = 10;
+		pub const MaxHistoryLength: u32 = 3;
+		pub const MaxCategoryNameLength: u32 = 8;
+		pub const MaxTags: u32 = 2;
+		pub const MaxTagLength: u32 = 4;
+	}
+	impl frame_system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type DbWeight = ();
+		type BlockExecutionWeight = ();
+		type ExtrinsicBaseWeight = ();
+		type MaximumExtrinsicWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+		type ModuleToIndex = ();
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+	}
+	impl pallet_timestamp::Trait for Test {
+		type Moment = u64;
+		type OnTimestampSet = ();
+		type MinimumPeriod = MinimumPeriod;
+	}
+	impl pallet_balances::Trait for Test {
+		type Balance = u64;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = frame_system::Module<Test>;
+	}
+	impl poe::Trait for Test {
+		type Event = ();
+		type MaxClaimLength = MaxClaimLength;
+		type MaxMemoLength = MaxMemoLength;
+		type Currency = pallet_balances::Module<Test>;
+		type ClaimDepositBase = ClaimDepositBase;
+		type ClaimDepositPerByte = ClaimDepositPerByte;
+		type RenewalFee = RenewalFee;
+		type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+		type MaxAttestations = MaxAttestations;
+		type DisputeBond = DisputeBond;
+		type ArbitratorOrigin = frame_system::EnsureRoot<u64>;
+		type MaxCoOwners = MaxCoOwners;
+		type ProposalLifetime = ProposalLifetime;
+		type MaxHistoryLength = MaxHistoryLength;
+		type CategoryOrigin = frame_system::EnsureRoot<u64>;
+		type MaxCategoryNameLength = MaxCategoryNameLength;
+		type MaxTags = MaxTags;
+		type MaxTagLength = MaxTagLength;
+	}
+
+	fn claim_info() -> poe::ClaimInfoOf<Test> {
+		ClaimInfo { owner: 1, block_number: 5, created_at: 42, memo: b"memo".to_vec() }
+	}
+
+	fn header(state_root: H256) -> Header {
+		Header {
+			parent_hash: Default::default(),
+			number: 1,
+			state_root,
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		}
+	}
+
+	/// A certificate for the claim `[0, 1]`, proven against a state holding `storage`.
+	fn prove(storage: BTreeMap<Vec<u8>, Vec<u8>>) -> (Certificate, Header) {
+		let claim = vec![0, 1];
+		let backend = InMemoryBackend::<BlakeTwo256>::from(storage);
+		let header = header(*backend.root());
+		let proof = prove_read(backend, &[poe::proof_storage_key::<Test>(&claim)]).unwrap();
+
+		let certificate = Certificate {
+			version: CERTIFICATE_VERSION,
+			claim: Bytes(claim),
+			claim_info: Bytes(claim_info().encode()),
+			block_hash: Bytes(header.hash().encode()),
+			header: Bytes(header.encode()),
+			proof: proof.iter_nodes().map(Bytes).collect(),
+		};
+		(certificate, header)
+	}
+
+	fn claimed() -> BTreeMap<Vec<u8>, Vec<u8>> {
+		let mut storage = BTreeMap::new();
+		storage.insert(poe::proof_storage_key::<Test>(&[0, 1]), claim_info().encode());
+		storage.insert(poe::proof_storage_key::<Test>(&[0, 2]), claim_info().encode());
+		storage
+	}
+
+	#[test]
+	fn json_roundtrip() {
+		let (certificate, _) = prove(claimed());
+		let json = certificate.to_json().unwrap();
+
+		assert!(json.contains("\"claimInfo\""));
+		assert_eq!(Certificate::from_json(&json).unwrap(), certificate);
+	}
+
+	#[test]
+	fn accepts_proven_claim() {
+		let (certificate, header) = prove(claimed());
+
+		assert_eq!(certificate.verify::<Test, Block>(&header), Ok(claim_info()));
+	}
+
+	#[test]
+	fn rejects_unknown_version() {
+		let (mut certificate, header) = prove(claimed());
+		certificate.version = CERTIFICATE_VERSION + 1;
+
+		assert_eq!(
+			certificate.verify::<Test, Block>(&header),
+			Err(Error::UnsupportedVersion(CERTIFICATE_VERSION + 1))
+		);
+	}
+
+	#[test]
+	fn rejects_untrusted_header() {
+		let (certificate, mut trusted) = prove(claimed());
+		trusted.number = 2;
+
+		assert_eq!(certificate.verify::<Test, Block>(&trusted), Err(Error::UntrustedHeader));
+	}
+
+	#[test]
+	fn rejects_proof_of_other_state() {
+		let (mut certificate, header) = prove(claimed());
+		let (other, _) = prove(BTreeMap::new());
+		certificate.proof = other.proof;
+
+		assert_eq!(certificate.verify::<Test, Block>(&header), Err(Error::InvalidProof));
+	}
+
+	#[test]
+	fn rejects_missing_claim() {
+		let mut storage = claimed();
+		storage.remove(&poe::proof_storage_key::<Test>(&[0, 1]));
+		let (certificate, header) = prove(storage);
+
+		assert_eq!(certificate.verify::<Test, Block>(&header), Err(Error::ClaimMismatch));
+	}
+
+	#[test]
+	fn rejects_altered_claim_info() {
+		let (mut certificate, header) = prove(claimed());
+		let mut altered = claim_info();
+		altered.owner = 2;
+		certificate.claim_info = Bytes(altered.encode());
+
+		assert_eq!(certificate.verify::<Test, Block>(&header), Err(Error::ClaimMismatch));
+	}
+}
@@ -0,0 +1,27 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Runtime API of the proof of existence pallet
+
+use codec::Codec;
+use sp_std::prelude::*;
+pub use poe::{Attestation, CategoryId, ClaimInfo, CustodyRecord};
+
+sp_api::decl_runtime_apis! {
+	pub trait PoeApi<AccountId, BlockNumber, Moment, Hash> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Moment: Codec,
+		Hash: Codec,
+	{
+		/// Claims owned by `account`, at most `limit` of them starting from index `cursor`.
+		fn claims_of(account: AccountId, cursor: u32, limit: u32) -> Vec<(Vec<u8>, ClaimInfo<AccountId, BlockNumber, Moment>)>;
+		/// Attestations on `claim` with their attesters, oldest first.
+		fn attestations_of(claim: Vec<u8>) -> Vec<(AccountId, Attestation<Hash, BlockNumber>)>;
+		/// Whether `leaf` is a document of the batch claim with the given Merkle `root`.
+		fn verify_inclusion(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool;
+		/// Owners of `claim` and when they acquired it, oldest first.
+		fn custody_history(claim: Vec<u8>) -> Vec<CustodyRecord<AccountId, BlockNumber, Moment>>;
+		/// Claims filed under `category`, at most `limit` of them starting from index `cursor`.
+		fn claims_in_category(category: CategoryId, cursor: u32, limit: u32) -> Vec<(Vec<u8>, ClaimInfo<AccountId, BlockNumber, Moment>)>;
+	}
+}
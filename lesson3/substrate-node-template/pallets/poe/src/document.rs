@@ -0,0 +1,49 @@
+//! Helpers to notarize documents by their hash, for use by clients.
+
+use std::{fs, io, path::Path};
+use codec::Decode;
+use sp_io::hashing::blake2_256;
+use sp_runtime::traits::Hash;
+use crate::{Call, Trait, merkle};
+
+/// The blake2-256 hash of a document.
+pub fn hash_document(data: &[u8]) -> [u8; 32] {
+	blake2_256(data)
+}
+
+/// The blake2-256 hash of a file.
+pub fn hash_file<P: AsRef<Path>>(path: P) -> io::Result<[u8; 32]> {
+	fs::read(path).map(|data| hash_document(&data))
+}
+
+/// The leaves and Merkle root of a batch of files, in the given order.
+pub fn hash_batch<H, P>(paths: &[P]) -> io::Result<(Vec<H::Output>, H::Output)> where
+	H: Hash,
+	P: AsRef<Path>,
+{
+	let leaves = paths.iter()
+		.map(|path| {
+			let digest = hash_file(path)?;
+			H::Output::decode(&mut &digest[..])
+				.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "runtime hash is not 32 bytes"))
+		})
+		.collect::<io::Result<Vec<_>>>()?;
+	let root = merkle::root::<H>(&leaves)
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no files to batch"))?;
+	Ok((leaves, root))
+}
+
+/// Build a `create_batch_claim` call for a batch of files.
+/// The leaves are returned as well, to produce inclusion proofs with `merkle::proof`.
+pub fn build_batch_claim_call<T: Trait, P: AsRef<Path>>(paths: &[P]) -> io::Result<(Call<T>, Vec<T::Hash>)> {
+	let (leaves, root) = hash_batch::<T::Hashing, _>(paths)?;
+	Ok((Call::create_batch_claim(root, leaves.len() as u32), leaves))
+}
+
+/// Build a `create_hashed_claim` call for a file.
+pub fn build_claim_call<T: Trait, P: AsRef<Path>>(path: P, memo: Vec<u8>, lifetime: Option<T::BlockNumber>) -> io::Result<Call<T>> {
+	let digest = hash_file(path)?;
+	let hash = T::Hash::decode(&mut &digest[..])
+		.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "runtime hash is not 32 bytes"))?;
+	Ok(Call::create_hashed_claim(hash, memo, lifetime))
+}
@@ -2,13 +2,20 @@
 
 /// A FRAME pallet proof of existence with necessary imports
 
+use codec::{Encode, Decode};
 use frame_support::{
-	decl_module, decl_storage, decl_event, decl_error, dispatch, ensure,
-	traits::{Get},
+	decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, StorageDoubleMap,
+	traits::{Get, Currency, ReservableCurrency, Imbalance, BalanceStatus, ExistenceRequirement, WithdrawReason, EnsureOrigin},
+	weights::Weight, IterableStorageMap, IterableStorageDoubleMap,
 };
 use frame_system::{self as system, ensure_signed};
-use sp_std::prelude::*;
-use sp_runtime::traits::StaticLookup;
+use sp_std::{prelude::*, vec};
+use sp_runtime::{RuntimeDebug, traits::{StaticLookup, Saturating, Zero, One, Hash}};
+
+#[cfg(feature = "std")]
+pub mod document;
+
+pub mod merkle;
 
 #[cfg(test)]
 mod mock;
@@ -17,7 +24,7 @@ mod mock;
 mod tests;
 
 /// The pallet's configuration trait.
-pub trait Trait: system::Trait {
+pub trait Trait: system::Trait + pallet_timestamp::Trait {
 	// Add other types and constants required to configure this pallet.
 
 	/// The overarching event type.
@@ -25,6 +32,209 @@ pub trait Trait: system::Trait {
 
 	// 附加题答案
 	type MaxClaimLength: Get<u32>;
+
+	/// The maximum length of the memo attached to a claim.
+	type MaxMemoLength: Get<u32>;
+
+	/// The currency the claim deposit is reserved in.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The base amount reserved for each claim.
+	type ClaimDepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount reserved for each byte of a claim, its memo and its tags.
+	type ClaimDepositPerByte: Get<BalanceOf<Self>>;
+
+	/// The fee burned by `renew_claim`.
+	type RenewalFee: Get<BalanceOf<Self>>;
+
+	/// The maximum number of claims pruned in one block. Zero is treated as one, so that
+	/// expired claims are always removed eventually.
+	type MaxExpiriesPerBlock: Get<u32>;
+
+	/// The maximum number of attestations on a claim.
+	type MaxAttestations: Get<u32>;
+
+	/// The amount a challenger reserves to dispute a claim.
+	type DisputeBond: Get<BalanceOf<Self>>;
+
+	/// The origin that rules on disputes.
+	type ArbitratorOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The maximum number of owners of a co-owned claim.
+	type MaxCoOwners: Get<u32>;
+
+	/// Number of blocks a proposal on a co-owned claim stays open.
+	type ProposalLifetime: Get<Self::BlockNumber>;
+
+	/// The maximum number of custody records kept per claim. The oldest are dropped first.
+	type MaxHistoryLength: Get<u32>;
+
+	/// The origin that manages the category registry.
+	type CategoryOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The maximum length of a category name.
+	type MaxCategoryNameLength: Get<u32>;
+
+	/// The maximum number of tags on a claim.
+	type MaxTags: Get<u32>;
+
+	/// The maximum length of a tag.
+	type MaxTagLength: Get<u32>;
+}
+
+/// Identifier of a category in the registry.
+pub type CategoryId = u32;
+
+/// Identifier of a token wrapping a claim.
+pub type TokenId = u64;
+
+/// Storage reads of pruning one expired claim.
+const PRUNE_CLAIM_READS: Weight = 10;
+
+/// Storage writes of pruning one expired claim, not counting its attestations.
+const PRUNE_CLAIM_WRITES: Weight = 23;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Information stored for each claim.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ClaimInfo<AccountId, BlockNumber, Moment> {
+	pub owner: AccountId,
+	/// Block the claim was created or last transferred in.
+	pub block_number: BlockNumber,
+	/// Timestamp the claim was created at.
+	pub created_at: Moment,
+	pub memo: Vec<u8>,
+}
+
+pub type ClaimInfoOf<T> = ClaimInfo<
+	<T as system::Trait>::AccountId,
+	<T as system::Trait>::BlockNumber,
+	<T as pallet_timestamp::Trait>::Moment,
+>;
+
+/// An owner of a claim and when they acquired it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct CustodyRecord<AccountId, BlockNumber, Moment> {
+	pub owner: AccountId,
+	pub block_number: BlockNumber,
+	pub moment: Moment,
+}
+
+pub type CustodyRecordOf<T> = CustodyRecord<
+	<T as system::Trait>::AccountId,
+	<T as system::Trait>::BlockNumber,
+	<T as pallet_timestamp::Trait>::Moment,
+>;
+
+/// A statement made by a third party about a claim.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Attestation<Hash, BlockNumber> {
+	/// Hash of the statement, e.g. a notary certificate.
+	pub statement_hash: Hash,
+	/// Block the attestation was made in.
+	pub block_number: BlockNumber,
+}
+
+pub type AttestationOf<T> = Attestation<<T as system::Trait>::Hash, <T as system::Trait>::BlockNumber>;
+
+/// A commitment made for a private claim, revealed later by `reveal_claim`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PrivateCommitment<AccountId, BlockNumber, Moment, Balance> {
+	pub owner: AccountId,
+	/// Block the commitment was made in, kept as the priority of the claim.
+	pub block_number: BlockNumber,
+	/// Timestamp the commitment was made at.
+	pub created_at: Moment,
+	/// Amount reserved until the commitment is revealed.
+	pub deposit: Balance,
+}
+
+pub type PrivateCommitmentOf<T> = PrivateCommitment<
+	<T as system::Trait>::AccountId,
+	<T as system::Trait>::BlockNumber,
+	<T as pallet_timestamp::Trait>::Moment,
+	BalanceOf<T>,
+>;
+
+/// The storage key of the `Proofs` entry of a claim, for clients reading or proving it.
+pub fn proof_storage_key<T: Trait>(claim: &[u8]) -> Vec<u8> {
+	Proofs::<T>::hashed_key_for(claim)
+}
+
+/// The commitment of a private claim, `hash(document_hash ++ salt)`.
+pub fn claim_commitment<H: Hash>(document_hash: &H::Output, salt: &H::Output) -> H::Output {
+	let mut data = document_hash.as_ref().to_vec();
+	data.extend_from_slice(salt.as_ref());
+	H::hash(&data)
+}
+
+/// A challenge of the ownership of a claim.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Dispute<AccountId, Hash, Balance, BlockNumber> {
+	pub challenger: AccountId,
+	/// Hash of the evidence presented to the arbitrator.
+	pub evidence: Hash,
+	/// Amount reserved from the challenger.
+	pub bond: Balance,
+	/// Block the dispute was opened in.
+	pub block_number: BlockNumber,
+}
+
+pub type DisputeOf<T> = Dispute<
+	<T as system::Trait>::AccountId,
+	<T as system::Trait>::Hash,
+	BalanceOf<T>,
+	<T as system::Trait>::BlockNumber,
+>;
+
+/// The owners of a co-owned claim and the number of them needed to act on it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct CoOwnership<AccountId> {
+	pub owners: Vec<AccountId>,
+	pub threshold: u32,
+}
+
+/// An action on a co-owned claim that needs the approval of its owners.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ClaimAction<AccountId> {
+	Revoke,
+	Transfer(AccountId),
+}
+
+/// An open proposal on a co-owned claim.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Proposal<AccountId, BlockNumber> {
+	pub action: ClaimAction<AccountId>,
+	pub proposer: AccountId,
+	/// Owners that approved the proposal, the proposer first.
+	pub approvals: Vec<AccountId>,
+	/// Block from which the proposal can no longer be approved.
+	pub expires_at: BlockNumber,
+}
+
+pub type ProposalOf<T> = Proposal<<T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+
+/// Storage layout versions, used to run migrations once.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	/// `Proofs` stores `(AccountId, BlockNumber)`.
+	V1,
+	/// `Proofs` stores `ClaimInfo`.
+	V2,
+	/// `ClaimsByOwner` indexes `Proofs`.
+	V3,
+	/// `ClaimHistory` records the owners of each claim.
+	V4,
+	/// `PruneCursor` tracks the first block not fully pruned.
+	V5,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
 }
 
 // This pallet's storage items.
@@ -33,15 +243,127 @@ decl_storage! {
 	// storage items are isolated from other pallets.
 	// ---------------------------------vvvvvvvvvvvvvv
 	trait Store for Module<T: Trait> as TemplateModule {
-		Proofs get(fn proofs): map hasher(blake2_128_concat) Vec<u8> => (T::AccountId, T::BlockNumber);
+		Proofs get(fn proofs): map hasher(blake2_128_concat) Vec<u8> => Option<ClaimInfoOf<T>>;
+
+		/// Claims owned by each account.
+		ClaimsByOwner: double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) Vec<u8> => ();
+
+		/// Owners of each claim, oldest first, at most `MaxHistoryLength` of them.
+		ClaimHistory get(fn claim_history): map hasher(blake2_128_concat) Vec<u8> => Vec<CustodyRecordOf<T>>;
+
+		/// Amount reserved from the owner of each claim.
+		ClaimDeposits get(fn claim_deposit): map hasher(blake2_128_concat) Vec<u8> => BalanceOf<T>;
+
+		/// Block at which a claim expires. None means it never expires.
+		ClaimExpiry get(fn claim_expiry): map hasher(blake2_128_concat) Vec<u8> => Option<T::BlockNumber>;
+
+		/// Claims to prune at each block.
+		ExpirySchedule: double_map hasher(twox_64_concat) T::BlockNumber, hasher(blake2_128_concat) Vec<u8> => ();
+		/// The first block whose expired claims may not all be pruned yet.
+		PruneCursor get(fn prune_cursor): T::BlockNumber;
+
+		/// Attestations on a claim by each attester.
+		Attestations get(fn attestation): double_map hasher(blake2_128_concat) Vec<u8>, hasher(blake2_128_concat) T::AccountId => Option<AttestationOf<T>>;
+		/// Accounts that attested a claim, in the order they did.
+		ClaimAttesters get(fn claim_attesters): map hasher(blake2_128_concat) Vec<u8> => Vec<T::AccountId>;
+
+		/// Number of documents in each batch claim, keyed by the encoded Merkle root.
+		BatchLeafCounts get(fn batch_leaf_count): map hasher(blake2_128_concat) Vec<u8> => Option<u32>;
+
+		/// Commitments of private claims not revealed yet.
+		PrivateCommitments get(fn private_commitment): map hasher(blake2_128_concat) T::Hash => Option<PrivateCommitmentOf<T>>;
+
+		/// Open disputes. A disputed claim cannot be transferred or revoked.
+		Disputes get(fn dispute): map hasher(blake2_128_concat) Vec<u8> => Option<DisputeOf<T>>;
+
+		/// Owners of co-owned claims. The deposit stays with the `ClaimInfo` owner.
+		CoOwners get(fn co_owners): map hasher(blake2_128_concat) Vec<u8> => Option<CoOwnership<T::AccountId>>;
+
+		/// The open proposal on each co-owned claim.
+		Proposals get(fn proposal): map hasher(blake2_128_concat) Vec<u8> => Option<ProposalOf<T>>;
+
+		/// Names of the registered categories.
+		Categories get(fn category): map hasher(twox_64_concat) CategoryId => Option<Vec<u8>>;
+		/// The id of the next category.
+		NextCategoryId get(fn next_category_id): CategoryId;
+
+		/// Category of each categorized claim.
+		ClaimCategory get(fn claim_category): map hasher(blake2_128_concat) Vec<u8> => Option<CategoryId>;
+		/// Claims in each category.
+		ClaimsByCategory: double_map hasher(twox_64_concat) CategoryId, hasher(blake2_128_concat) Vec<u8> => ();
+
+		/// Free-form tags of each claim.
+		ClaimTags get(fn claim_tags): map hasher(blake2_128_concat) Vec<u8> => Vec<Vec<u8>>;
+
+		/// The claim wrapped by each token.
+		TokenClaims get(fn token_claim): map hasher(twox_64_concat) TokenId => Option<Vec<u8>>;
+		/// The token wrapping each wrapped claim. A wrapped claim cannot be transferred or revoked.
+		WrappedClaims get(fn wrapped_claim): map hasher(blake2_128_concat) Vec<u8> => Option<TokenId>;
+		/// The holder of each token.
+		TokenOwners get(fn token_owner): map hasher(twox_64_concat) TokenId => Option<T::AccountId>;
+		/// The price of each token for sale.
+		TokenPrices get(fn token_price): map hasher(twox_64_concat) TokenId => Option<BalanceOf<T>>;
+		/// The id of the next token.
+		NextTokenId get(fn next_token_id): TokenId;
+
+		/// Storage layout version. New chains start at the latest one.
+		StorageVersion build(|_| Releases::V5): Releases;
 	}
 }
 
 // The pallet's events
 decl_event!(
-	pub enum Event<T> where AccountId = <T as system::Trait>::AccountId {
-		ClaimCreated(AccountId, Vec<u8>),
+	pub enum Event<T> where
+		AccountId = <T as system::Trait>::AccountId,
+		BlockNumber = <T as system::Trait>::BlockNumber,
+		Moment = <T as pallet_timestamp::Trait>::Moment,
+		Hash = <T as system::Trait>::Hash,
+		ClaimAction = ClaimAction<<T as system::Trait>::AccountId>,
+		Balance = BalanceOf<T>,
+	{
+		/// A claim is created. (owner, claim, block_number, created_at)
+		ClaimCreated(AccountId, Vec<u8>, BlockNumber, Moment),
 		ClaimRevoked(AccountId, Vec<u8>),
+		/// A claim expiry is extended. (owner, claim, expires_at)
+		ClaimRenewed(AccountId, Vec<u8>, BlockNumber),
+		/// A claim expired and was removed. (owner, claim)
+		ClaimExpired(AccountId, Vec<u8>),
+		/// A claim is attested. (attester, claim, statement_hash)
+		Attested(AccountId, Vec<u8>, Hash),
+		/// An attestation is revoked. (attester, claim)
+		AttestationRevoked(AccountId, Vec<u8>),
+		/// A private claim is committed. (owner, commitment)
+		ClaimCommitted(AccountId, Hash),
+		/// A claim is disputed. (challenger, claim, evidence)
+		ClaimDisputed(AccountId, Vec<u8>, Hash),
+		/// A dispute is resolved. (claim, challenger, challenger_won)
+		DisputeResolved(Vec<u8>, AccountId, bool),
+		/// An action on a co-owned claim is proposed. (proposer, claim, action)
+		ProposalCreated(AccountId, Vec<u8>, ClaimAction),
+		/// A proposal is approved by an owner. (owner, claim)
+		ProposalApproved(AccountId, Vec<u8>),
+		/// A proposal reached its threshold and was executed. (claim, action)
+		ProposalExecuted(Vec<u8>, ClaimAction),
+		/// A proposal is cancelled by its proposer. (claim)
+		ProposalCancelled(Vec<u8>),
+		/// A claim changed owner. (from, to, claim)
+		ClaimTransferred(AccountId, AccountId, Vec<u8>),
+		/// A category is registered. (category, name)
+		CategoryCreated(CategoryId, Vec<u8>),
+		/// A category is removed from the registry. (category)
+		CategoryRemoved(CategoryId),
+		/// A claim is wrapped into a token. (owner, claim, token)
+		ClaimWrapped(AccountId, Vec<u8>, TokenId),
+		/// A token is unwrapped, its holder owns the claim. (holder, claim, token)
+		ClaimUnwrapped(AccountId, Vec<u8>, TokenId),
+		/// A token changed holder. (from, to, token)
+		TokenTransferred(AccountId, AccountId, TokenId),
+		/// A token is listed for sale, or delisted with None. (holder, token, price)
+		TokenAsk(AccountId, TokenId, Option<Balance>),
+		/// A token is sold. (seller, buyer, token, price)
+		TokenSold(AccountId, AccountId, TokenId, Balance),
+		/// A private claim commitment is withdrawn. (owner, commitment)
+		CommitmentCancelled(AccountId, Hash),
 	}
 );
 
@@ -52,6 +374,37 @@ decl_error! {
 		ClaimNotExist,
 		NotClaimOwner,
 		ProofTooLong,
+		MemoTooLong,
+		InvalidLifetime,
+		ClaimNotExpiring,
+		AlreadyAttested,
+		AttestationNotExist,
+		TooManyAttestations,
+		EmptyBatch,
+		CommitmentAlreadyExist,
+		CommitmentNotExist,
+		InvalidReveal,
+		ClaimDisputed,
+		DisputeNotExist,
+		CannotDisputeOwnClaim,
+		TooManyCoOwners,
+		InvalidThreshold,
+		ProposalPending,
+		ProposalNotExist,
+		ProposalExpired,
+		AlreadyApproved,
+		CategoryNotExist,
+		CategoryInUse,
+		CategoryNameTooLong,
+		TooManyTags,
+		TagTooLong,
+		ClaimIsWrapped,
+		ClaimCoOwned,
+		TokenNotExist,
+		NotTokenOwner,
+		TokenNotForSale,
+		PriceTooLow,
+		InsufficientBalance,
 	}
 }
 
@@ -68,18 +421,199 @@ decl_module! {
 		// this is needed only if you are using events in your pallet
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_to_v2()
+				.saturating_add(Self::migrate_to_v3())
+				.saturating_add(Self::migrate_to_v4())
+				.saturating_add(Self::migrate_to_v5())
+		}
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			Self::prune_expired(now)
+		}
+
 		#[weight = 0]
-		pub fn create_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+		pub fn create_claim(origin, claim: Vec<u8>, memo: Vec<u8>, lifetime: Option<T::BlockNumber>, category: Option<CategoryId>, tags: Vec<Vec<u8>>) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
-
 			// 附加题答案
 			ensure!(T::MaxClaimLength::get() >= claim.len() as u32, Error::<T>::ProofTooLong);
 
-			Proofs::<T>::insert(&claim, (sender.clone(), system::Module::<T>::block_number()));
+			if let Some(category) = category {
+				ensure!(Categories::contains_key(category), Error::<T>::CategoryNotExist);
+			}
 
-			Self::deposit_event(RawEvent::ClaimCreated(sender, claim));
+			ensure!(tags.len() as u32 <= T::MaxTags::get(), Error::<T>::TooManyTags);
+			ensure!(tags.iter().all(|tag| tag.len() as u32 <= T::MaxTagLength::get()), Error::<T>::TagTooLong);
+
+			// The tags and the category index are paid for with the claim
+			let index_deposit = Self::index_deposit_for(&claim, category, &tags);
+			Self::do_create_claim(sender, claim.clone(), memo, lifetime, index_deposit)?;
+
+			if let Some(category) = category {
+				ClaimCategory::insert(&claim, category);
+				ClaimsByCategory::insert(category, &claim, ());
+			}
+			if !tags.is_empty() {
+				ClaimTags::insert(&claim, tags);
+			}
+
+			Ok(())
+		}
+
+		/// Register a category claims can be filed under.
+		#[weight = 0]
+		pub fn create_category(origin, name: Vec<u8>) -> dispatch::DispatchResult {
+			T::CategoryOrigin::ensure_origin(origin)?;
+
+			ensure!(name.len() as u32 <= T::MaxCategoryNameLength::get(), Error::<T>::CategoryNameTooLong);
+
+			let category = NextCategoryId::get();
+			NextCategoryId::put(category.wrapping_add(1));
+			Categories::insert(category, &name);
+
+			Self::deposit_event(RawEvent::CategoryCreated(category, name));
+
+			Ok(())
+		}
+
+		/// Remove a category no claim is filed under.
+		#[weight = 0]
+		pub fn remove_category(origin, category: CategoryId) -> dispatch::DispatchResult {
+			T::CategoryOrigin::ensure_origin(origin)?;
+
+			ensure!(Categories::contains_key(category), Error::<T>::CategoryNotExist);
+
+			ensure!(ClaimsByCategory::iter_prefix(category).next().is_none(), Error::<T>::CategoryInUse);
+
+			Categories::remove(category);
+
+			Self::deposit_event(RawEvent::CategoryRemoved(category));
+
+			Ok(())
+		}
+
+		/// Create a claim owned by the sender and `co_owners`. Revoking or transferring it
+		/// needs the approval of `threshold` of them, see `approve_proposal`.
+		#[weight = 0]
+		pub fn create_co_owned_claim(origin, claim: Vec<u8>, memo: Vec<u8>, co_owners: Vec<T::AccountId>, threshold: u32) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(T::MaxClaimLength::get() >= claim.len() as u32, Error::<T>::ProofTooLong);
+
+			let mut owners = vec![sender.clone()];
+			for owner in co_owners {
+				if !owners.contains(&owner) {
+					owners.push(owner);
+				}
+			}
+
+			ensure!(owners.len() as u32 <= T::MaxCoOwners::get(), Error::<T>::TooManyCoOwners);
+			ensure!(threshold > 0 && threshold as usize <= owners.len(), Error::<T>::InvalidThreshold);
+
+			Self::do_create_claim(sender, claim.clone(), memo, None, Zero::zero())?;
+			for owner in owners.iter().skip(1) {
+				ClaimsByOwner::<T>::insert(owner, &claim, ());
+			}
+			CoOwners::<T>::insert(&claim, CoOwnership { owners, threshold });
+
+			Ok(())
+		}
+
+		/// Create a claim keyed by the hash of a document instead of its content.
+		/// The claim is stored under the encoded hash, which is not subject to `MaxClaimLength`.
+		#[weight = 0]
+		pub fn create_hashed_claim(origin, hash: T::Hash, memo: Vec<u8>, lifetime: Option<T::BlockNumber>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_create_claim(sender, hash.encode(), memo, lifetime, Zero::zero())
+		}
+
+		/// Claim a batch of documents at once by the Merkle root of their hashes.
+		/// The batch is stored as a claim under the encoded root and revoked or transferred as one.
+		#[weight = 0]
+		pub fn create_batch_claim(origin, root: T::Hash, leaf_count: u32) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(leaf_count > 0, Error::<T>::EmptyBatch);
+
+			let claim = root.encode();
+			Self::do_create_claim(sender, claim.clone(), Vec::new(), None, Zero::zero())?;
+			BatchLeafCounts::insert(&claim, leaf_count);
+
+			Ok(())
+		}
+
+		/// Commit to a document without disclosing its hash, see `claim_commitment`.
+		#[weight = 0]
+		pub fn commit_claim(origin, commitment: T::Hash) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(!PrivateCommitments::<T>::contains_key(&commitment), Error::<T>::CommitmentAlreadyExist);
+
+			let deposit = T::ClaimDepositBase::get();
+			T::Currency::reserve(&sender, deposit)?;
+
+			PrivateCommitments::<T>::insert(&commitment, PrivateCommitment {
+				owner: sender.clone(),
+				block_number: system::Module::<T>::block_number(),
+				created_at: pallet_timestamp::Module::<T>::get(),
+				deposit,
+			});
+
+			Self::deposit_event(RawEvent::ClaimCommitted(sender, commitment));
+
+			Ok(())
+		}
+
+		/// Reveal a committed document hash and its salt, creating a claim dated at the commitment.
+		#[weight = 0]
+		pub fn reveal_claim(origin, document_hash: T::Hash, salt: T::Hash, memo: Vec<u8>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let commitment = claim_commitment::<T::Hashing>(&document_hash, &salt);
+			let committed = Self::private_commitment(&commitment).ok_or(Error::<T>::InvalidReveal)?;
+
+			ensure!(committed.owner == sender, Error::<T>::NotClaimOwner);
+
+			ensure!(T::MaxMemoLength::get() >= memo.len() as u32, Error::<T>::MemoTooLong);
+
+			let claim = document_hash.encode();
+			ensure!(T::Currency::can_reserve(&sender, Self::deposit_for(&claim, &memo)), Error::<T>::InsufficientBalance);
+
+			// A claim of the same document made after the commitment, e.g. by someone who saw
+			// the reveal, gives way to it
+			if let Some(claim_info) = Proofs::<T>::get(&claim) {
+				ensure!(committed.created_at < claim_info.created_at, Error::<T>::ProofAlreadyExist);
+				// A claim that others hold a stake in stays until that stake is settled
+				ensure!(!WrappedClaims::contains_key(&claim), Error::<T>::ClaimIsWrapped);
+				ensure!(!Disputes::<T>::contains_key(&claim), Error::<T>::ClaimDisputed);
+				ensure!(!CoOwners::<T>::contains_key(&claim), Error::<T>::ClaimCoOwned);
+				Self::remove_claim(&claim_info.owner, &claim);
+				Self::deposit_event(RawEvent::ClaimRevoked(claim_info.owner, claim.clone()));
+			}
+
+			// The claim deposit replaces the commitment deposit
+			Self::do_create_claim_at(sender.clone(), claim, memo, None, Zero::zero(), committed.block_number, committed.created_at)?;
+			T::Currency::unreserve(&sender, committed.deposit);
+			PrivateCommitments::<T>::remove(&commitment);
+
+			Ok(())
+		}
+
+		/// Withdraw a commitment that will not be revealed and release its deposit.
+		#[weight = 0]
+		pub fn cancel_commitment(origin, commitment: T::Hash) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let committed = Self::private_commitment(&commitment).ok_or(Error::<T>::CommitmentNotExist)?;
+
+			ensure!(committed.owner == sender, Error::<T>::NotClaimOwner);
+
+			T::Currency::unreserve(&sender, committed.deposit);
+			PrivateCommitments::<T>::remove(&commitment);
+
+			Self::deposit_event(RawEvent::CommitmentCancelled(sender, commitment));
 
 			Ok(())
 		}
@@ -88,13 +622,19 @@ decl_module! {
 		pub fn revoke_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
+			let claim_info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
-			let (owner, _block_number) = Proofs::<T>::get(&claim);
+			ensure!(!WrappedClaims::contains_key(&claim), Error::<T>::ClaimIsWrapped);
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			if CoOwners::<T>::contains_key(&claim) {
+				return Self::propose(sender, claim, ClaimAction::Revoke);
+			}
 
-			Proofs::<T>::remove(&claim);
+			ensure!(claim_info.owner == sender, Error::<T>::NotClaimOwner);
+
+			ensure!(!Disputes::<T>::contains_key(&claim), Error::<T>::ClaimDisputed);
+
+			Self::remove_claim(&sender, &claim);
 
 			Self::deposit_event(RawEvent::ClaimRevoked(sender, claim));
 
@@ -106,17 +646,696 @@ decl_module! {
 		pub fn transfer_claim(origin, claim: Vec<u8>, dest: <T::Lookup as StaticLookup>::Source) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
+			let claim_info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
-			let (owner, _block_number) = Proofs::<T>::get(&claim);
+			let dest = T::Lookup::lookup(dest)?;
+
+			ensure!(!WrappedClaims::contains_key(&claim), Error::<T>::ClaimIsWrapped);
+
+			if CoOwners::<T>::contains_key(&claim) {
+				return Self::propose(sender, claim, ClaimAction::Transfer(dest));
+			}
+
+			ensure!(claim_info.owner == sender, Error::<T>::NotClaimOwner);
+
+			ensure!(!Disputes::<T>::contains_key(&claim), Error::<T>::ClaimDisputed);
+
+			Self::do_transfer_claim(&claim, claim_info, dest)
+		}
+
+		/// Wrap a claim into a token that can be traded. The claim is locked until the token is unwrapped.
+		#[weight = 0]
+		pub fn wrap_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let claim_info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(claim_info.owner == sender, Error::<T>::NotClaimOwner);
+
+			ensure!(!CoOwners::<T>::contains_key(&claim), Error::<T>::ClaimCoOwned);
+
+			ensure!(!WrappedClaims::contains_key(&claim), Error::<T>::ClaimIsWrapped);
+
+			ensure!(!Disputes::<T>::contains_key(&claim), Error::<T>::ClaimDisputed);
+
+			let token = NextTokenId::get();
+			NextTokenId::put(token.wrapping_add(1));
+			TokenClaims::insert(token, &claim);
+			WrappedClaims::insert(&claim, token);
+			TokenOwners::<T>::insert(token, &sender);
+
+			Self::deposit_event(RawEvent::ClaimWrapped(sender, claim, token));
+
+			Ok(())
+		}
+
+		/// Burn a token, transferring the wrapped claim and its deposit to the holder.
+		#[weight = 0]
+		pub fn unwrap_claim(origin, token: TokenId) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let holder = Self::token_owner(token).ok_or(Error::<T>::TokenNotExist)?;
+
+			ensure!(holder == sender, Error::<T>::NotTokenOwner);
+
+			let claim = Self::token_claim(token).ok_or(Error::<T>::TokenNotExist)?;
+			let claim_info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(!Disputes::<T>::contains_key(&claim), Error::<T>::ClaimDisputed);
+
+			Self::burn_token(&claim);
+
+			if claim_info.owner != sender {
+				Self::do_transfer_claim(&claim, claim_info, sender.clone())?;
+			}
+
+			Self::deposit_event(RawEvent::ClaimUnwrapped(sender, claim, token));
+
+			Ok(())
+		}
+
+		#[weight = 0]
+		pub fn transfer_token(origin, dest: <T::Lookup as StaticLookup>::Source, token: TokenId) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let holder = Self::token_owner(token).ok_or(Error::<T>::TokenNotExist)?;
+
+			ensure!(holder == sender, Error::<T>::NotTokenOwner);
 
 			let dest = T::Lookup::lookup(dest)?;
 
-			Proofs::<T>::insert(&claim, (dest, system::Module::<T>::block_number()));
+			TokenPrices::<T>::remove(token);
+			TokenOwners::<T>::insert(token, &dest);
+
+			Self::deposit_event(RawEvent::TokenTransferred(sender, dest, token));
 
 			Ok(())
 		}
+
+		/// Set a price for a token for sale
+		/// None to delist the token
+		#[weight = 0]
+		pub fn ask_token(origin, token: TokenId, new_price: Option<BalanceOf<T>>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let holder = Self::token_owner(token).ok_or(Error::<T>::TokenNotExist)?;
+
+			ensure!(holder == sender, Error::<T>::NotTokenOwner);
+
+			TokenPrices::<T>::mutate_exists(token, |price| *price = new_price);
+
+			Self::deposit_event(RawEvent::TokenAsk(sender, token, new_price));
+
+			Ok(())
+		}
+
+		/// Buy a token for at most `max_price`.
+		#[weight = 0]
+		pub fn buy_token(origin, token: TokenId, max_price: BalanceOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let holder = Self::token_owner(token).ok_or(Error::<T>::TokenNotExist)?;
+
+			let price = Self::token_price(token).ok_or(Error::<T>::TokenNotForSale)?;
+
+			ensure!(max_price >= price, Error::<T>::PriceTooLow);
+
+			T::Currency::transfer(&sender, &holder, price, ExistenceRequirement::KeepAlive)?;
+
+			TokenPrices::<T>::remove(token);
+			TokenOwners::<T>::insert(token, &sender);
+
+			Self::deposit_event(RawEvent::TokenSold(holder, sender, token, price));
+
+			Ok(())
+		}
+
+		/// Approve the open proposal on a co-owned claim, executing it once it reaches the threshold.
+		#[weight = 0]
+		pub fn approve_proposal(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let mut proposal = Self::proposal(&claim).ok_or(Error::<T>::ProposalNotExist)?;
+			let co_ownership = Self::co_owners(&claim).ok_or(Error::<T>::ProposalNotExist)?;
+
+			ensure!(co_ownership.owners.contains(&sender), Error::<T>::NotClaimOwner);
+
+			ensure!(system::Module::<T>::block_number() < proposal.expires_at, Error::<T>::ProposalExpired);
+
+			ensure!(!proposal.approvals.contains(&sender), Error::<T>::AlreadyApproved);
+
+			ensure!(!Disputes::<T>::contains_key(&claim), Error::<T>::ClaimDisputed);
+
+			proposal.approvals.push(sender.clone());
+			let action = proposal.action.clone();
+
+			let executed = Self::try_execute(&claim, proposal, co_ownership.threshold)?;
+
+			Self::deposit_event(RawEvent::ProposalApproved(sender, claim.clone()));
+			if executed {
+				Self::deposit_event(RawEvent::ProposalExecuted(claim, action));
+			}
+
+			Ok(())
+		}
+
+		/// Cancel a proposal made by the sender.
+		#[weight = 0]
+		pub fn cancel_proposal(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let proposal = Self::proposal(&claim).ok_or(Error::<T>::ProposalNotExist)?;
+
+			// Anyone may clean up an expired proposal
+			let expired = system::Module::<T>::block_number() >= proposal.expires_at;
+			ensure!(expired || proposal.proposer == sender, Error::<T>::NotClaimOwner);
+
+			Proposals::<T>::remove(&claim);
+
+			Self::deposit_event(RawEvent::ProposalCancelled(claim));
+
+			Ok(())
+		}
+
+		/// Challenge the ownership of a claim, reserving `DisputeBond`.
+		#[weight = 0]
+		pub fn dispute_claim(origin, claim: Vec<u8>, evidence: T::Hash) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let claim_info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(!Self::is_owner(&claim, &claim_info, &sender), Error::<T>::CannotDisputeOwnClaim);
+
+			// The owner of a wrapped claim may have sold it, a ruling would not reach the token holder
+			ensure!(!WrappedClaims::contains_key(&claim), Error::<T>::ClaimIsWrapped);
+
+			ensure!(!Disputes::<T>::contains_key(&claim), Error::<T>::ClaimDisputed);
+
+			let bond = T::DisputeBond::get();
+			T::Currency::reserve(&sender, bond)?;
+
+			Disputes::<T>::insert(&claim, Dispute {
+				challenger: sender.clone(),
+				evidence,
+				bond,
+				block_number: system::Module::<T>::block_number(),
+			});
+
+			Self::deposit_event(RawEvent::ClaimDisputed(sender, claim, evidence));
+
+			Ok(())
+		}
+
+		/// Rule on a dispute. If the challenger wins the claim is transferred to them
+		/// and the bond returned, otherwise the bond is slashed.
+		#[weight = 0]
+		pub fn resolve_dispute(origin, claim: Vec<u8>, challenger_won: bool) -> dispatch::DispatchResult {
+			T::ArbitratorOrigin::ensure_origin(origin)?;
+
+			let dispute = Self::dispute(&claim).ok_or(Error::<T>::DisputeNotExist)?;
+
+			Disputes::<T>::remove(&claim);
+
+			if challenger_won {
+				T::Currency::unreserve(&dispute.challenger, dispute.bond);
+				if let Some(claim_info) = Proofs::<T>::get(&claim) {
+					Self::do_transfer_claim(&claim, claim_info, dispute.challenger.clone())?;
+				}
+			} else {
+				let _ = T::Currency::slash_reserved(&dispute.challenger, dispute.bond);
+			}
+
+			Self::deposit_event(RawEvent::DisputeResolved(claim, dispute.challenger, challenger_won));
+
+			Ok(())
+		}
+
+		/// Extend the expiry of a claim by `extension` blocks, burning `RenewalFee`.
+		#[weight = 0]
+		pub fn renew_claim(origin, claim: Vec<u8>, extension: T::BlockNumber) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let claim_info = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			// A wrapped claim is renewed by the token holder, who loses the token when it expires
+			match WrappedClaims::get(&claim) {
+				Some(token) => ensure!(Self::token_owner(token) == Some(sender.clone()), Error::<T>::NotTokenOwner),
+				None => ensure!(Self::is_owner(&claim, &claim_info, &sender), Error::<T>::NotClaimOwner),
+			}
+
+			let expires_at = Self::claim_expiry(&claim).ok_or(Error::<T>::ClaimNotExpiring)?;
+
+			ensure!(!extension.is_zero(), Error::<T>::InvalidLifetime);
+
+			let _ = T::Currency::withdraw(&sender, T::RenewalFee::get(), WithdrawReason::Fee.into(), ExistenceRequirement::KeepAlive)?;
+
+			let new_expires_at = expires_at.saturating_add(extension);
+			Self::unschedule_expiry(&claim, expires_at);
+			Self::schedule_expiry(&claim, new_expires_at);
+
+			Self::deposit_event(RawEvent::ClaimRenewed(sender, claim, new_expires_at));
+
+			Ok(())
+		}
+
+		/// Attest an existing claim with the hash of a statement.
+		#[weight = 0]
+		pub fn attest(origin, claim: Vec<u8>, statement_hash: T::Hash) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
+
+			ensure!(!Attestations::<T>::contains_key(&claim, &sender), Error::<T>::AlreadyAttested);
+
+			let attesters = Self::claim_attesters(&claim);
+			ensure!((attesters.len() as u32) < T::MaxAttestations::get(), Error::<T>::TooManyAttestations);
+
+			Attestations::<T>::insert(&claim, &sender, Attestation {
+				statement_hash,
+				block_number: system::Module::<T>::block_number(),
+			});
+			ClaimAttesters::<T>::append_or_put(&claim, sp_std::iter::once(&sender));
+
+			Self::deposit_event(RawEvent::Attested(sender, claim, statement_hash));
+
+			Ok(())
+		}
+
+		/// Revoke an attestation made by the sender.
+		#[weight = 0]
+		pub fn revoke_attestation(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Attestations::<T>::contains_key(&claim, &sender), Error::<T>::AttestationNotExist);
+
+			Attestations::<T>::remove(&claim, &sender);
+			ClaimAttesters::<T>::mutate_exists(&claim, |maybe_attesters| {
+				if let Some(attesters) = maybe_attesters {
+					attesters.retain(|a| a != &sender);
+					if attesters.is_empty() {
+						*maybe_attesters = None;
+					}
+				}
+			});
+
+			Self::deposit_event(RawEvent::AttestationRevoked(sender, claim));
+
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Claims of `owner` with their info, starting at index `cursor` of `ClaimsByOwner`.
+	pub fn claims_of(owner: &T::AccountId, cursor: u32, limit: u32) -> Vec<(Vec<u8>, ClaimInfoOf<T>)> {
+		ClaimsByOwner::<T>::iter_prefix(owner)
+			.map(|(claim, ())| claim)
+			.skip(cursor as usize)
+			.take(limit as usize)
+			.filter_map(|claim| Self::proofs(&claim).map(|info| (claim, info)))
+			.collect()
+	}
+
+	/// Claims filed under `category`, starting at index `cursor` of `ClaimsByCategory`.
+	pub fn claims_in_category(category: CategoryId, cursor: u32, limit: u32) -> Vec<(Vec<u8>, ClaimInfoOf<T>)> {
+		ClaimsByCategory::iter_prefix(category)
+			.map(|(claim, ())| claim)
+			.skip(cursor as usize)
+			.take(limit as usize)
+			.filter_map(|claim| Self::proofs(&claim).map(|info| (claim, info)))
+			.collect()
+	}
+
+	/// Whether `leaf` is a document of the batch claim with the given `root`.
+	pub fn verify_batch_inclusion(root: T::Hash, leaf: T::Hash, proof: Vec<T::Hash>) -> bool {
+		BatchLeafCounts::get(root.encode())
+			.map_or(false, |leaf_count| merkle::verify_inclusion::<T::Hashing>(&root, &leaf, leaf_count, &proof))
+	}
+
+	/// Attestations on a claim with their attesters, oldest first.
+	pub fn attestations_of(claim: &Vec<u8>) -> Vec<(T::AccountId, AttestationOf<T>)> {
+		Self::claim_attesters(claim)
+			.into_iter()
+			.filter_map(|attester| Self::attestation(claim, &attester).map(|a| (attester, a)))
+			.collect()
+	}
+
+	/// The deposit reserved for a claim with the given memo.
+	pub fn deposit_for(claim: &[u8], memo: &[u8]) -> BalanceOf<T> {
+		let bytes = BalanceOf::<T>::from((claim.len() + memo.len()) as u32);
+		T::ClaimDepositBase::get().saturating_add(T::ClaimDepositPerByte::get().saturating_mul(bytes))
+	}
+
+	/// The deposit reserved on top of `deposit_for` for the tags of a claim and its entry in the
+	/// index of its category.
+	pub fn index_deposit_for(claim: &[u8], category: Option<CategoryId>, tags: &[Vec<u8>]) -> BalanceOf<T> {
+		let index_bytes = if category.is_some() { claim.len() } else { 0 };
+		let tag_bytes = tags.iter().map(|tag| tag.len()).sum::<usize>();
+		T::ClaimDepositPerByte::get().saturating_mul(BalanceOf::<T>::from((index_bytes + tag_bytes) as u32))
+	}
+
+	/// Whether `who` owns the claim, alone or as one of its co-owners.
+	fn is_owner(claim: &Vec<u8>, claim_info: &ClaimInfoOf<T>, who: &T::AccountId) -> bool {
+		match Self::co_owners(claim) {
+			Some(co_ownership) => co_ownership.owners.contains(who),
+			None => &claim_info.owner == who,
+		}
+	}
+
+	/// Open a proposal on a co-owned claim, approved by the proposer.
+	/// An expired proposal is replaced, an open one has to be approved or cancelled first.
+	fn propose(sender: T::AccountId, claim: Vec<u8>, action: ClaimAction<T::AccountId>) -> dispatch::DispatchResult {
+		let co_ownership = Self::co_owners(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+		ensure!(co_ownership.owners.contains(&sender), Error::<T>::NotClaimOwner);
+
+		ensure!(!Disputes::<T>::contains_key(&claim), Error::<T>::ClaimDisputed);
+
+		let now = system::Module::<T>::block_number();
+		if let Some(proposal) = Self::proposal(&claim) {
+			ensure!(now >= proposal.expires_at, Error::<T>::ProposalPending);
+		}
+
+		let proposal = Proposal {
+			action: action.clone(),
+			proposer: sender.clone(),
+			approvals: vec![sender.clone()],
+			expires_at: now.saturating_add(T::ProposalLifetime::get()),
+		};
+
+		let executed = Self::try_execute(&claim, proposal, co_ownership.threshold)?;
+
+		Self::deposit_event(RawEvent::ProposalCreated(sender, claim.clone(), action.clone()));
+		if executed {
+			Self::deposit_event(RawEvent::ProposalExecuted(claim, action));
+		}
+
+		Ok(())
+	}
+
+	/// Execute a proposal if it has `threshold` approvals, otherwise store it.
+	fn try_execute(claim: &Vec<u8>, proposal: ProposalOf<T>, threshold: u32) -> Result<bool, dispatch::DispatchError> {
+		if (proposal.approvals.len() as u32) < threshold {
+			Proposals::<T>::insert(claim, proposal);
+			return Ok(false);
+		}
+
+		let claim_info = Proofs::<T>::get(claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+		match proposal.action {
+			ClaimAction::Revoke => {
+				Self::remove_claim(&claim_info.owner, claim);
+				Self::deposit_event(RawEvent::ClaimRevoked(claim_info.owner, claim.clone()));
+			}
+			ClaimAction::Transfer(dest) => Self::do_transfer_claim(claim, claim_info, dest)?,
+		}
+
+		Ok(true)
+	}
+
+	fn do_create_claim(
+		sender: T::AccountId,
+		claim: Vec<u8>,
+		memo: Vec<u8>,
+		lifetime: Option<T::BlockNumber>,
+		index_deposit: BalanceOf<T>,
+	) -> dispatch::DispatchResult {
+		let block_number = system::Module::<T>::block_number();
+		let created_at = pallet_timestamp::Module::<T>::get();
+
+		Self::do_create_claim_at(sender, claim, memo, lifetime, index_deposit, block_number, created_at)
+	}
+
+	/// Create a claim dated at `block_number` and `created_at`, which may be in the past.
+	/// `index_deposit` is reserved along with the claim deposit, see `index_deposit_for`.
+	fn do_create_claim_at(
+		sender: T::AccountId,
+		claim: Vec<u8>,
+		memo: Vec<u8>,
+		lifetime: Option<T::BlockNumber>,
+		index_deposit: BalanceOf<T>,
+		block_number: T::BlockNumber,
+		created_at: T::Moment,
+	) -> dispatch::DispatchResult {
+		ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+
+		ensure!(T::MaxMemoLength::get() >= memo.len() as u32, Error::<T>::MemoTooLong);
+
+		// The current block is already pruned, so a claim has to live at least one block
+		ensure!(lifetime.map_or(true, |l| !l.is_zero()), Error::<T>::InvalidLifetime);
+
+		let deposit = Self::deposit_for(&claim, &memo).saturating_add(index_deposit);
+		T::Currency::reserve(&sender, deposit)?;
+		ClaimDeposits::<T>::insert(&claim, deposit);
+
+		Proofs::<T>::insert(&claim, ClaimInfo {
+			owner: sender.clone(),
+			block_number,
+			created_at,
+			memo,
+		});
+		ClaimsByOwner::<T>::insert(&sender, &claim, ());
+		Self::record_custody(&claim, sender.clone(), block_number, created_at);
+
+		if let Some(lifetime) = lifetime {
+			let now = system::Module::<T>::block_number();
+			Self::schedule_expiry(&claim, now.saturating_add(lifetime));
+		}
+
+		Self::deposit_event(RawEvent::ClaimCreated(sender, claim, block_number, created_at));
+
+		Ok(())
+	}
+
+	/// Move a claim and its deposit to `dest`, who becomes its single owner.
+	fn do_transfer_claim(claim: &Vec<u8>, mut claim_info: ClaimInfoOf<T>, dest: T::AccountId) -> dispatch::DispatchResult {
+		let owner = claim_info.owner.clone();
+
+		// The deposit follows the claim to its new owner
+		let deposit = Self::claim_deposit(claim);
+		let moved = if deposit.is_zero() {
+			deposit
+		} else if T::Currency::total_balance(&dest).is_zero() {
+			// `repatriate_reserved` cannot create the account of `dest`. The deposit opens it as free
+			// balance and is reserved again, or goes back to the owner if it is too small to do so
+			if deposit < T::Currency::minimum_balance() {
+				T::Currency::unreserve(&owner, deposit);
+				Zero::zero()
+			} else {
+				let (imbalance, _) = T::Currency::slash_reserved(&owner, deposit);
+				let moved = imbalance.peek();
+				T::Currency::resolve_creating(&dest, imbalance);
+				// A new account has no locks, reserving its whole balance cannot fail
+				let _ = T::Currency::reserve(&dest, moved);
+				moved
+			}
+		} else {
+			let remaining = T::Currency::repatriate_reserved(&owner, &dest, deposit, BalanceStatus::Reserved)?;
+			deposit.saturating_sub(remaining)
+		};
+		ClaimDeposits::<T>::insert(claim, moved);
+
+		Self::remove_owners(&owner, claim);
+		ClaimsByOwner::<T>::insert(&dest, claim, ());
+
+		let block_number = system::Module::<T>::block_number();
+		Self::record_custody(claim, dest.clone(), block_number, pallet_timestamp::Module::<T>::get());
+
+		claim_info.owner = dest.clone();
+		claim_info.block_number = block_number;
+		Proofs::<T>::insert(claim, claim_info);
+
+		Proposals::<T>::remove(claim);
+		Self::burn_token(claim);
+
+		Self::deposit_event(RawEvent::ClaimTransferred(owner, dest, claim.clone()));
+
+		Ok(())
+	}
+
+	/// Append an owner to the custody history of a claim, dropping the oldest records beyond `MaxHistoryLength`.
+	fn record_custody(claim: &Vec<u8>, owner: T::AccountId, block_number: T::BlockNumber, moment: T::Moment) {
+		ClaimHistory::<T>::mutate(claim, |history| {
+			history.push(CustodyRecord { owner, block_number, moment });
+			let max = T::MaxHistoryLength::get() as usize;
+			if history.len() > max {
+				let excess = history.len() - max;
+				history.drain(..excess);
+			}
+		});
+	}
+
+	/// Remove a claim with everything attached to it and return the deposit to `owner`.
+	fn remove_claim(owner: &T::AccountId, claim: &Vec<u8>) {
+		Proofs::<T>::remove(claim);
+		Self::remove_owners(owner, claim);
+
+		T::Currency::unreserve(owner, ClaimDeposits::<T>::take(claim));
+
+		if let Some(expires_at) = Self::claim_expiry(claim) {
+			Self::unschedule_expiry(claim, expires_at);
+		}
+
+		for attester in ClaimAttesters::<T>::take(claim) {
+			Attestations::<T>::remove(claim, &attester);
+		}
+
+		BatchLeafCounts::remove(claim);
+
+		ClaimHistory::<T>::remove(claim);
+
+		ClaimTags::remove(claim);
+		if let Some(category) = ClaimCategory::take(claim) {
+			ClaimsByCategory::remove(category, claim);
+		}
+
+		Proposals::<T>::remove(claim);
+		Self::burn_token(claim);
+
+		// A claim can still expire while disputed, the challenger gets the bond back
+		if let Some(dispute) = Disputes::<T>::take(claim) {
+			T::Currency::unreserve(&dispute.challenger, dispute.bond);
+		}
+	}
+
+	/// Drop a claim from the index of its owner and co-owners and end its co-ownership.
+	fn remove_owners(owner: &T::AccountId, claim: &Vec<u8>) {
+		ClaimsByOwner::<T>::remove(owner, claim);
+		if let Some(co_ownership) = CoOwners::<T>::take(claim) {
+			for co_owner in co_ownership.owners {
+				ClaimsByOwner::<T>::remove(&co_owner, claim);
+			}
+		}
+	}
+
+	/// Destroy the token wrapping a claim, if any.
+	fn burn_token(claim: &Vec<u8>) {
+		if let Some(token) = WrappedClaims::take(claim) {
+			TokenClaims::remove(token);
+			TokenOwners::<T>::remove(token);
+			TokenPrices::<T>::remove(token);
+		}
+	}
+
+	fn schedule_expiry(claim: &Vec<u8>, expires_at: T::BlockNumber) {
+		ClaimExpiry::<T>::insert(claim, expires_at);
+		ExpirySchedule::<T>::insert(expires_at, claim, ());
+	}
+
+	fn unschedule_expiry(claim: &Vec<u8>, expires_at: T::BlockNumber) {
+		ClaimExpiry::<T>::remove(claim);
+		ExpirySchedule::<T>::remove(expires_at, claim);
+	}
+
+	/// Remove claims whose expiry is at or before `now`, oldest first and at most
+	/// `MaxExpiriesPerBlock` of them. `PruneCursor` stays on the first block not fully pruned,
+	/// so a backlog is worked off in the next blocks without rescheduling any claim.
+	fn prune_expired(now: T::BlockNumber) -> Weight {
+		let db_weight = T::DbWeight::get();
+		let max = T::MaxExpiriesPerBlock::get().max(1) as usize;
+		let mut cursor = Self::prune_cursor();
+		let mut pruned = 0;
+		let mut scanned = 0;
+
+		// Empty blocks are bounded too, a lagging cursor still catches up as it skips more than one per block
+		while cursor <= now && pruned < max && scanned <= max {
+			let claims: Vec<Vec<u8>> = ExpirySchedule::<T>::iter_prefix(cursor)
+				.map(|(claim, ())| claim)
+				.take(max - pruned)
+				.collect();
+			scanned += 1;
+			pruned += claims.len();
+
+			for claim in claims {
+				Self::unschedule_expiry(&claim, cursor);
+				if let Some(claim_info) = Proofs::<T>::get(&claim) {
+					Self::remove_claim(&claim_info.owner, &claim);
+					Self::deposit_event(RawEvent::ClaimExpired(claim_info.owner, claim));
+				}
+			}
+
+			if pruned < max {
+				cursor = cursor + One::one();
+			}
+		}
+		PruneCursor::<T>::put(cursor);
+
+		let pruned = pruned as Weight;
+		db_weight.reads_writes(1 + scanned as Weight, 1)
+			.saturating_add(db_weight.reads_writes(
+				PRUNE_CLAIM_READS.saturating_mul(pruned),
+				(PRUNE_CLAIM_WRITES + T::MaxAttestations::get() as Weight).saturating_mul(pruned),
+			))
+	}
+
+	/// Convert `(AccountId, BlockNumber)` entries of `Proofs` to `ClaimInfo`.
+	/// The creation time of old claims is unknown and left as the default.
+	fn migrate_to_v2() -> Weight {
+		if StorageVersion::get() != Releases::V1 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		Proofs::<T>::translate(|_claim, (owner, block_number): (T::AccountId, T::BlockNumber)| {
+			Some(ClaimInfo {
+				owner,
+				block_number,
+				created_at: Default::default(),
+				memo: Vec::new(),
+			})
+		});
+
+		StorageVersion::put(Releases::V2);
+
+		// `translate` does not report how many entries it converted, they are counted again
+		let migrated = Proofs::<T>::iter().count() as Weight;
+		T::DbWeight::get().reads_writes(1 + 2 * migrated, 1 + migrated)
+	}
+
+	/// Build `ClaimsByOwner` from the existing `Proofs`.
+	fn migrate_to_v3() -> Weight {
+		if StorageVersion::get() != Releases::V2 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut migrated = 0;
+		for (claim, claim_info) in Proofs::<T>::iter() {
+			ClaimsByOwner::<T>::insert(&claim_info.owner, &claim, ());
+			migrated += 1;
+		}
+
+		StorageVersion::put(Releases::V3);
+
+		T::DbWeight::get().reads_writes(1 + migrated, 1 + migrated)
+	}
+
+	/// Start the custody history of existing claims with their current owner.
+	fn migrate_to_v4() -> Weight {
+		if StorageVersion::get() != Releases::V3 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut migrated = 0;
+		for (claim, claim_info) in Proofs::<T>::iter() {
+			Self::record_custody(&claim, claim_info.owner, claim_info.block_number, claim_info.created_at);
+			migrated += 1;
+		}
+
+		StorageVersion::put(Releases::V4);
+
+		// Each claim is read along with its history
+		T::DbWeight::get().reads_writes(1 + 2 * migrated, 1 + migrated)
+	}
+
+	/// Start pruning at the current block. Expiries before it were pruned in their own block.
+	fn migrate_to_v5() -> Weight {
+		if StorageVersion::get() != Releases::V4 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		PruneCursor::<T>::put(system::Module::<T>::block_number());
+
+		StorageVersion::put(Releases::V5);
+
+		T::DbWeight::get().reads_writes(2, 2)
 	}
 }
@@ -0,0 +1,87 @@
+//! A binary Merkle tree over document hashes, used for batch claims.
+//!
+//! Leaves are hashed with a `0x00` prefix and inner nodes with a `0x01` prefix, so an inner
+//! node can never pass as a leaf. Pairs are hashed in sorted order and a node without a
+//! sibling is paired with itself, so a proof is the list of exactly `depth(leaf_count)`
+//! siblings from the leaf up to the root.
+
+use sp_runtime::traits::Hash;
+use sp_std::prelude::*;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+fn hash_leaf<H: Hash>(leaf: &H::Output) -> H::Output {
+	let mut data = Vec::with_capacity(1 + leaf.as_ref().len());
+	data.push(LEAF_PREFIX);
+	data.extend_from_slice(leaf.as_ref());
+	H::hash(&data)
+}
+
+fn hash_pair<H: Hash>(a: &H::Output, b: &H::Output) -> H::Output {
+	let (first, second) = if a.as_ref() <= b.as_ref() { (a, b) } else { (b, a) };
+	let mut data = Vec::with_capacity(1 + first.as_ref().len() + second.as_ref().len());
+	data.push(NODE_PREFIX);
+	data.extend_from_slice(first.as_ref());
+	data.extend_from_slice(second.as_ref());
+	H::hash(&data)
+}
+
+fn next_layer<H: Hash>(layer: &[H::Output]) -> Vec<H::Output> {
+	layer.chunks(2)
+		.map(|pair| match pair {
+			[a, b] => hash_pair::<H>(a, b),
+			[a] => hash_pair::<H>(a, a),
+			_ => unreachable!("chunks(2) yields one or two items; qed"),
+		})
+		.collect()
+}
+
+fn hash_leaves<H: Hash>(leaves: &[H::Output]) -> Vec<H::Output> {
+	leaves.iter().map(hash_leaf::<H>).collect()
+}
+
+/// The length of every proof in a tree of `leaf_count` leaves, i.e. `ceil(log2(leaf_count))`.
+pub fn depth(leaf_count: u32) -> usize {
+	let mut depth = 0;
+	while (1u64 << depth) < leaf_count as u64 {
+		depth += 1;
+	}
+	depth
+}
+
+/// The root of a tree over `leaves`, None if there are none.
+pub fn root<H: Hash>(leaves: &[H::Output]) -> Option<H::Output> {
+	if leaves.is_empty() {
+		return None;
+	}
+	let mut layer = hash_leaves::<H>(leaves);
+	while layer.len() > 1 {
+		layer = next_layer::<H>(&layer);
+	}
+	layer.pop()
+}
+
+/// The inclusion proof of the leaf at `index`, None if it is out of range.
+pub fn proof<H: Hash>(leaves: &[H::Output], mut index: usize) -> Option<Vec<H::Output>> {
+	if index >= leaves.len() {
+		return None;
+	}
+	let mut siblings = Vec::new();
+	let mut layer = hash_leaves::<H>(leaves);
+	while layer.len() > 1 {
+		siblings.push(*layer.get(index ^ 1).unwrap_or(&layer[index]));
+		layer = next_layer::<H>(&layer);
+		index /= 2;
+	}
+	Some(siblings)
+}
+
+/// Whether `leaf` is part of the tree of `leaf_count` leaves with the given `root`.
+pub fn verify_inclusion<H: Hash>(root: &H::Output, leaf: &H::Output, leaf_count: u32, proof: &[H::Output]) -> bool {
+	if leaf_count == 0 || proof.len() != depth(leaf_count) {
+		return false;
+	}
+	let computed = proof.iter().fold(hash_leaf::<H>(leaf), |node, sibling| hash_pair::<H>(&node, sibling));
+	&computed == root
+}
@@ -2,7 +2,7 @@
 
 use crate::{Module, Trait};
 use sp_core::H256;
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, weights::Weight};
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
 };
@@ -12,6 +12,18 @@ impl_outer_origin! {
 	pub enum Origin for Test {}
 }
 
+mod poe {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		pallet_balances<T>,
+		poe<T>,
+	}
+}
+
 // For testing the pallet, we construct most of a mock runtime. This means
 // first constructing a configuration type (`Test`) which `impl`s each of the
 // configuration traits of pallets we want to use.
@@ -33,7 +45,7 @@ impl system::Trait for Test {
 	type AccountId = u64;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = TestEvent;
 	type BlockHashCount = BlockHashCount;
 	type MaximumBlockWeight = MaximumBlockWeight;
 	type DbWeight = ();
@@ -44,22 +56,78 @@ impl system::Trait for Test {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type ModuleToIndex = ();
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 }
 
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = TestEvent;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
 parameter_types! {
 	pub const MaxClaimLength: u32 = 6;
+	pub const MaxMemoLength: u32 = 8;
+	pub const ClaimDepositBase: u64 = 2;
+	pub const ClaimDepositPerByte: u64 = 1;
+	pub const RenewalFee: u64 = 5;
+	pub const MaxExpiriesPerBlock: u32 = 2;
+	pub const MaxAttestations: u32 = 2;
+	pub const DisputeBond: u64 = 10;
+	pub const MaxCoOwners: u32 = 3;
+	pub const ProposalLifetime: u64 = 10;
+	pub const MaxHistoryLength: u32 = 3;
+	pub const MaxCategoryNameLength: u32 = 8;
+	pub const MaxTags: u32 = 2;
+	pub const MaxTagLength: u32 = 4;
 }
 impl Trait for Test {
-	type Event = ();
+	type Event = TestEvent;
 	type MaxClaimLength = MaxClaimLength;
+	type MaxMemoLength = MaxMemoLength;
+	type Currency = Balances;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
+	type RenewalFee = RenewalFee;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxAttestations = MaxAttestations;
+	type DisputeBond = DisputeBond;
+	type ArbitratorOrigin = system::EnsureRoot<u64>;
+	type MaxCoOwners = MaxCoOwners;
+	type ProposalLifetime = ProposalLifetime;
+	type MaxHistoryLength = MaxHistoryLength;
+	type CategoryOrigin = system::EnsureRoot<u64>;
+	type MaxCategoryNameLength = MaxCategoryNameLength;
+	type MaxTags = MaxTags;
+	type MaxTagLength = MaxTagLength;
 }
 pub type PoeModule = Module<Test>;
+pub type System = system::Module<Test>;
+pub type Timestamp = pallet_timestamp::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
 
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100), (2, 100), (3, 3)],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
 }
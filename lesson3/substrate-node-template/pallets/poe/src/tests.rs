@@ -1,5 +1,1025 @@
 // Tests to be written here
 
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use crate::{Error, RawEvent, Call, ClaimInfo, ClaimAction, CoOwnership, CustodyRecord, CoOwners, Proofs, ClaimsByOwner, ExpirySchedule, Releases, StorageVersion, claim_commitment, proof_storage_key, document, merkle, mock::*};
+use sp_core::H256;
+use sp_runtime::traits::BlakeTwo256;
+use frame_support::{assert_ok, assert_noop, storage::unhashed, traits::{OnRuntimeUpgrade, OnInitialize}, StorageMap, StorageValue, IterableStorageDoubleMap};
 
+
+#[test]
+fn create_claim_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		Timestamp::set_timestamp(42);
+		let claim = vec![0, 1];
+
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone(), b"memo".to_vec(), None, None, vec![]));
+
+		assert_eq!(PoeModule::proofs(&claim), Some(ClaimInfo {
+			owner: 1,
+			block_number: 5,
+			created_at: 42,
+			memo: b"memo".to_vec(),
+		}));
+	});
+}
+
+#[test]
+fn create_claim_failed_when_claim_already_exist() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone(), vec![], None, None, vec![]);
+
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), claim, vec![], None, None, vec![]),
+			Error::<Test>::ProofAlreadyExist
+		);
+	});
+}
+
+#[test]
+fn create_claim_failed_when_memo_too_long() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![0; 9], None, None, vec![]),
+			Error::<Test>::MemoTooLong
+		);
+	});
+}
+
+#[test]
+fn revoke_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone(), vec![], None, None, vec![]);
+
+		assert_noop!(
+			PoeModule::revoke_claim(Origin::signed(2), claim.clone()),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim.clone()));
+		assert_eq!(PoeModule::proofs(&claim), None);
+	});
+}
+
+#[test]
+fn transfer_claim_keeps_metadata() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(42);
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone(), b"memo".to_vec(), None, None, vec![]);
+
+		System::set_block_number(3);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+
+		assert_eq!(PoeModule::proofs(&claim), Some(ClaimInfo {
+			owner: 2,
+			block_number: 3,
+			created_at: 42,
+			memo: b"memo".to_vec(),
+		}));
+	});
+}
+
+fn owned_by(owner: u64) -> Vec<Vec<u8>> {
+	let mut claims: Vec<Vec<u8>> = ClaimsByOwner::<Test>::iter_prefix(owner).map(|(claim, ())| claim).collect();
+	claims.sort();
+	claims
+}
+
+#[test]
+fn migrate_to_v2_converts_tuple_claims() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		unhashed::put(&Proofs::<Test>::hashed_key_for(&claim), &(1u64, 7u64));
+		StorageVersion::put(Releases::V1);
+
+		PoeModule::on_runtime_upgrade();
+
+		assert_eq!(PoeModule::proofs(&claim), Some(ClaimInfo {
+			owner: 1,
+			block_number: 7,
+			created_at: 0,
+			memo: vec![],
+		}));
+		assert_eq!(owned_by(1), vec![claim.clone()]);
+		assert_eq!(PoeModule::claim_history(&claim), vec![CustodyRecord { owner: 1, block_number: 7, moment: 0 }]);
+		assert_eq!(StorageVersion::get(), Releases::V5);
+	});
+}
+
+#[test]
+fn migrate_to_v5_starts_pruning_at_upgrade() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(100);
+		StorageVersion::put(Releases::V4);
+
+		PoeModule::on_runtime_upgrade();
+
+		assert_eq!(PoeModule::prune_cursor(), 100);
+		assert_eq!(StorageVersion::get(), Releases::V5);
+	});
+}
+
+#[test]
+fn claims_by_owner_follows_claim_ownership() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![1], vec![], None, None, vec![]);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![2], vec![], None, None, vec![]);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![3], vec![], None, None, vec![]);
+		assert_eq!(owned_by(1), vec![vec![1], vec![2], vec![3]]);
+
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), vec![2], 2));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), vec![3]));
+
+		assert_eq!(owned_by(1), vec![vec![1]]);
+		assert_eq!(owned_by(2), vec![vec![2]]);
+	});
+}
+
+#[test]
+fn claims_of_pages_through_claims() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![1], vec![], None, None, vec![]);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![2], vec![], None, None, vec![]);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![3], vec![], None, None, vec![]);
+
+		let first = PoeModule::claims_of(&1, 0, 1);
+		assert_eq!(first.len(), 1);
+		assert_eq!(first[0].1.owner, 1);
+
+		let rest = PoeModule::claims_of(&1, 1, 5);
+		assert_eq!(rest.len(), 2);
+
+		let mut claims: Vec<Vec<u8>> = first.into_iter().chain(rest).map(|(claim, _)| claim).collect();
+		claims.sort();
+		assert_eq!(claims, vec![vec![1], vec![2], vec![3]]);
+	});
+}
+
+#[test]
+fn create_hashed_claim_ignores_max_claim_length() {
+	new_test_ext().execute_with(|| {
+		let hash = H256::from(document::hash_document(b"a long document"));
+
+		assert_ok!(PoeModule::create_hashed_claim(Origin::signed(1), hash, vec![], None));
+
+		assert_eq!(PoeModule::proofs(hash.as_bytes().to_vec()).map(|info| info.owner), Some(1));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), hash.as_bytes().to_vec()));
+	});
+}
+
+#[test]
+fn build_claim_call_hashes_file() {
+	let path = std::env::temp_dir().join("poe_build_claim_call_test");
+	std::fs::write(&path, b"a long document").unwrap();
+
+	let call = document::build_claim_call::<Test, _>(&path, b"memo".to_vec(), None).unwrap();
+	std::fs::remove_file(&path).unwrap();
+
+	let hash = H256::from(document::hash_document(b"a long document"));
+	assert_eq!(call, Call::create_hashed_claim(hash, b"memo".to_vec(), None));
+}
+
+#[test]
+fn create_claim_reserves_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![0, 1, 2], None, None, vec![]));
+
+		assert_eq!(PoeModule::claim_deposit(vec![0, 1]), 7);
+		assert_eq!(Balances::reserved_balance(1), 7);
+		assert_eq!(Balances::free_balance(1), 93);
+	});
+}
+
+#[test]
+fn create_claim_failed_with_insufficient_balance() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(3), vec![0, 1], vec![], None, None, vec![]),
+			pallet_balances::Error::<Test, _>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn revoke_claim_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), vec![0, 1]));
+
+		assert_eq!(PoeModule::claim_deposit(vec![0, 1]), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+	});
+}
+
+#[test]
+fn transfer_claim_moves_deposit() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), vec![0, 1], 2));
+
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 96);
+		assert_eq!(Balances::reserved_balance(2), 4);
+		assert_eq!(Balances::free_balance(2), 100);
+		assert_eq!(PoeModule::claim_deposit(vec![0, 1]), 4);
+	});
+}
+
+fn expiring_at(block: u64) -> Vec<Vec<u8>> {
+	ExpirySchedule::<Test>::iter_prefix(block).map(|(claim, ())| claim).collect()
+}
+
+#[test]
+fn transfer_claim_to_new_account_moves_deposit() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), vec![0, 1], 4));
+
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 96);
+		assert_eq!(Balances::reserved_balance(4), 4);
+		assert_eq!(Balances::free_balance(4), 0);
+		assert_eq!(PoeModule::claim_deposit(vec![0, 1]), 4);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(4), vec![0, 1]));
+		assert_eq!(Balances::free_balance(4), 4);
+	});
+}
+
+#[test]
+fn expired_claims_are_pruned() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], Some(3), None, vec![]));
+		assert_eq!(PoeModule::claim_expiry(vec![0, 1]), Some(4));
+
+		PoeModule::on_initialize(3);
+		assert!(PoeModule::proofs(vec![0, 1]).is_some());
+
+		PoeModule::on_initialize(4);
+		assert_eq!(PoeModule::proofs(vec![0, 1]), None);
+		assert_eq!(PoeModule::claim_expiry(vec![0, 1]), None);
+		assert!(owned_by(1).is_empty());
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn expiry_is_pruned_in_batches() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![1], vec![], Some(1), None, vec![]);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![2], vec![], Some(1), None, vec![]);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![3], vec![], Some(1), None, vec![]);
+
+		PoeModule::on_initialize(2);
+		let left = owned_by(1);
+		assert_eq!(left.len(), 1);
+		assert_eq!(expiring_at(2), left);
+		assert_eq!(PoeModule::claim_expiry(&left[0]), Some(2));
+		assert_eq!(PoeModule::prune_cursor(), 2);
+
+		PoeModule::on_initialize(3);
+		assert!(owned_by(1).is_empty());
+		assert!(expiring_at(2).is_empty());
+		assert_eq!(PoeModule::prune_cursor(), 4);
+	});
+}
+
+#[test]
+fn pruning_catches_up_with_a_backlog() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		for claim in 1..=5 {
+			let _ = PoeModule::create_claim(Origin::signed(1), vec![claim], vec![], Some(1), None, vec![]);
+		}
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![6], vec![], Some(2), None, vec![]);
+
+		PoeModule::on_initialize(2);
+		PoeModule::on_initialize(3);
+		assert_eq!(owned_by(1).len(), 2);
+		assert_eq!(PoeModule::prune_cursor(), 2);
+
+		PoeModule::on_initialize(4);
+		assert!(owned_by(1).is_empty());
+		assert_eq!(PoeModule::prune_cursor(), 3);
+	});
+}
+
+#[test]
+fn renew_claim_extends_expiry_for_a_fee() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], Some(3), None, vec![]);
+
+		assert_ok!(PoeModule::renew_claim(Origin::signed(1), vec![0, 1], 10));
+
+		assert_eq!(PoeModule::claim_expiry(vec![0, 1]), Some(14));
+		assert!(expiring_at(4).is_empty());
+		assert_eq!(expiring_at(14), vec![vec![0, 1]]);
+		assert_eq!(Balances::free_balance(1), 100 - 4 - 5);
+
+		PoeModule::on_initialize(4);
+		assert!(PoeModule::proofs(vec![0, 1]).is_some());
+	});
+}
+
+#[test]
+fn renew_claim_fails_for_permanent_claim() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+
+		assert_noop!(
+			PoeModule::renew_claim(Origin::signed(1), vec![0, 1], 10),
+			Error::<Test>::ClaimNotExpiring
+		);
+	});
+}
+
+#[test]
+fn revoke_claim_unschedules_expiry() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], Some(3), None, vec![]);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), vec![0, 1]));
+
+		assert!(expiring_at(4).is_empty());
+		assert_eq!(PoeModule::claim_expiry(vec![0, 1]), None);
+	});
+}
+
+#[test]
+fn attest_works() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+		let statement = H256::repeat_byte(1);
+
+		assert_ok!(PoeModule::attest(Origin::signed(2), vec![0, 1], statement));
+		assert_noop!(
+			PoeModule::attest(Origin::signed(2), vec![0, 1], statement),
+			Error::<Test>::AlreadyAttested
+		);
+		assert_ok!(PoeModule::attest(Origin::signed(3), vec![0, 1], statement));
+		assert_noop!(
+			PoeModule::attest(Origin::signed(4), vec![0, 1], statement),
+			Error::<Test>::TooManyAttestations
+		);
+
+		let attesters: Vec<u64> = PoeModule::attestations_of(&vec![0, 1]).into_iter().map(|(a, _)| a).collect();
+		assert_eq!(attesters, vec![2, 3]);
+		assert_eq!(PoeModule::attestation(vec![0, 1], 2).map(|a| a.statement_hash), Some(statement));
+	});
+}
+
+#[test]
+fn attest_failed_when_claim_not_exist() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::attest(Origin::signed(2), vec![0, 1], H256::repeat_byte(1)),
+			Error::<Test>::ClaimNotExist
+		);
+	});
+}
+
+#[test]
+fn revoke_attestation_works() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+		let _ = PoeModule::attest(Origin::signed(2), vec![0, 1], H256::repeat_byte(1));
+
+		assert_noop!(
+			PoeModule::revoke_attestation(Origin::signed(3), vec![0, 1]),
+			Error::<Test>::AttestationNotExist
+		);
+		assert_ok!(PoeModule::revoke_attestation(Origin::signed(2), vec![0, 1]));
+
+		assert_eq!(PoeModule::attestation(vec![0, 1], 2), None);
+		assert!(PoeModule::claim_attesters(vec![0, 1]).is_empty());
+	});
+}
+
+#[test]
+fn revoke_claim_removes_attestations() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+		let _ = PoeModule::attest(Origin::signed(2), vec![0, 1], H256::repeat_byte(1));
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), vec![0, 1]));
+
+		assert_eq!(PoeModule::attestation(vec![0, 1], 2), None);
+		assert!(PoeModule::attestations_of(&vec![0, 1]).is_empty());
+	});
+}
+
+fn leaves(count: u8) -> Vec<H256> {
+	(0..count).map(|i| H256::repeat_byte(i)).collect()
+}
+
+#[test]
+fn merkle_proofs_verify_for_every_leaf() {
+	for count in 1..=5 {
+		let leaves = leaves(count);
+		let root = merkle::root::<BlakeTwo256>(&leaves).unwrap();
+
+		for (index, leaf) in leaves.iter().enumerate() {
+			let proof = merkle::proof::<BlakeTwo256>(&leaves, index).unwrap();
+			assert_eq!(proof.len(), merkle::depth(count as u32));
+			assert!(merkle::verify_inclusion::<BlakeTwo256>(&root, leaf, count as u32, &proof));
+		}
+	}
+}
+
+#[test]
+fn merkle_proof_rejects_other_leaf() {
+	let leaves = leaves(4);
+	let root = merkle::root::<BlakeTwo256>(&leaves).unwrap();
+	let proof = merkle::proof::<BlakeTwo256>(&leaves, 0).unwrap();
+
+	assert!(!merkle::verify_inclusion::<BlakeTwo256>(&root, &H256::repeat_byte(9), 4, &proof));
+	assert_eq!(merkle::proof::<BlakeTwo256>(&leaves, 4), None);
+	assert_eq!(merkle::root::<BlakeTwo256>(&[]), None);
+}
+
+#[test]
+fn merkle_proof_rejects_inner_node_as_leaf() {
+	let leaves = leaves(4);
+	let root = merkle::root::<BlakeTwo256>(&leaves).unwrap();
+	let proof = merkle::proof::<BlakeTwo256>(&leaves, 0).unwrap();
+
+	// The parent of the first two leaves, proven with the last sibling of the first leaf
+	let inner = merkle::root::<BlakeTwo256>(&leaves[..2]).unwrap();
+	assert!(!merkle::verify_inclusion::<BlakeTwo256>(&root, &inner, 4, &proof[1..]));
+	assert!(!merkle::verify_inclusion::<BlakeTwo256>(&root, &root, 4, &[]));
+	assert!(!merkle::verify_inclusion::<BlakeTwo256>(&root, &leaves[0], 8, &proof));
+}
+
+#[test]
+fn create_batch_claim_works() {
+	new_test_ext().execute_with(|| {
+		let leaves = leaves(3);
+		let root = merkle::root::<BlakeTwo256>(&leaves).unwrap();
+		let proof = merkle::proof::<BlakeTwo256>(&leaves, 2).unwrap();
+
+		assert!(!PoeModule::verify_batch_inclusion(root, leaves[2], proof.clone()));
+		assert_noop!(PoeModule::create_batch_claim(Origin::signed(1), root, 0), Error::<Test>::EmptyBatch);
+		assert_ok!(PoeModule::create_batch_claim(Origin::signed(1), root, 3));
+
+		assert_eq!(PoeModule::batch_leaf_count(root.as_bytes().to_vec()), Some(3));
+		assert!(PoeModule::verify_batch_inclusion(root, leaves[2], proof.clone()));
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), root.as_bytes().to_vec()));
+		assert_eq!(PoeModule::batch_leaf_count(root.as_bytes().to_vec()), None);
+		assert!(!PoeModule::verify_batch_inclusion(root, leaves[2], proof));
+	});
+}
+
+#[test]
+fn reveal_claim_keeps_commit_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(2);
+		Timestamp::set_timestamp(20);
+		let document = H256::repeat_byte(1);
+		let salt = H256::repeat_byte(2);
+		let commitment = claim_commitment::<BlakeTwo256>(&document, &salt);
+
+		assert_ok!(PoeModule::commit_claim(Origin::signed(1), commitment));
+		assert_eq!(Balances::reserved_balance(1), 2);
+
+		System::set_block_number(5);
+		Timestamp::set_timestamp(50);
+		assert_noop!(
+			PoeModule::reveal_claim(Origin::signed(1), document, H256::repeat_byte(3), vec![]),
+			Error::<Test>::InvalidReveal
+		);
+		assert_noop!(
+			PoeModule::reveal_claim(Origin::signed(2), document, salt, vec![]),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::reveal_claim(Origin::signed(1), document, salt, vec![]));
+
+		let claim_info = PoeModule::proofs(document.as_bytes().to_vec()).unwrap();
+		assert_eq!(claim_info.owner, 1);
+		assert_eq!(claim_info.block_number, 2);
+		assert_eq!(claim_info.created_at, 20);
+		assert_eq!(PoeModule::private_commitment(commitment), None);
+		assert_eq!(Balances::reserved_balance(1), PoeModule::deposit_for(document.as_bytes(), &[]));
+	});
+}
+
+#[test]
+fn reveal_claim_overrides_later_claim() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(10);
+		let document = H256::repeat_byte(1);
+		let salt = H256::repeat_byte(2);
+		let commitment = claim_commitment::<BlakeTwo256>(&document, &salt);
+
+		let _ = PoeModule::commit_claim(Origin::signed(1), commitment);
+
+		Timestamp::set_timestamp(20);
+		let _ = PoeModule::create_hashed_claim(Origin::signed(2), document, vec![], None);
+
+		assert_ok!(PoeModule::reveal_claim(Origin::signed(1), document, salt, vec![]));
+
+		let claim_info = PoeModule::proofs(document.as_bytes().to_vec()).unwrap();
+		assert_eq!(claim_info.owner, 1);
+		assert_eq!(claim_info.created_at, 10);
+		assert_eq!(Balances::reserved_balance(1), PoeModule::deposit_for(document.as_bytes(), &[]));
+		assert_eq!(Balances::reserved_balance(2), 0);
+	});
+}
+
+#[test]
+fn reveal_claim_failed_when_claim_is_older() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(10);
+		let document = H256::repeat_byte(1);
+		let salt = H256::repeat_byte(2);
+		let commitment = claim_commitment::<BlakeTwo256>(&document, &salt);
+
+		let _ = PoeModule::create_hashed_claim(Origin::signed(2), document, vec![], None);
+
+		Timestamp::set_timestamp(20);
+		let _ = PoeModule::commit_claim(Origin::signed(1), commitment);
+
+		assert_noop!(
+			PoeModule::reveal_claim(Origin::signed(1), document, salt, vec![]),
+			Error::<Test>::ProofAlreadyExist
+		);
+		assert_eq!(Balances::reserved_balance(1), 2);
+	});
+}
+
+/// Commit to a document as 1 and return its hash and salt, with the clock moved past the commitment.
+fn commit_document() -> (H256, H256) {
+	Timestamp::set_timestamp(10);
+	let document = H256::repeat_byte(1);
+	let salt = H256::repeat_byte(2);
+	let _ = PoeModule::commit_claim(Origin::signed(1), claim_commitment::<BlakeTwo256>(&document, &salt));
+	Timestamp::set_timestamp(20);
+	(document, salt)
+}
+
+#[test]
+fn reveal_claim_failed_when_later_claim_is_wrapped() {
+	new_test_ext().execute_with(|| {
+		let (document, salt) = commit_document();
+		let _ = PoeModule::create_hashed_claim(Origin::signed(2), document, vec![], None);
+		let _ = PoeModule::wrap_claim(Origin::signed(2), document.as_bytes().to_vec());
+
+		assert_noop!(
+			PoeModule::reveal_claim(Origin::signed(1), document, salt, vec![]),
+			Error::<Test>::ClaimIsWrapped
+		);
+	});
+}
+
+#[test]
+fn reveal_claim_failed_when_later_claim_is_disputed() {
+	new_test_ext().execute_with(|| {
+		let (document, salt) = commit_document();
+		let _ = PoeModule::create_hashed_claim(Origin::signed(2), document, vec![], None);
+		let _ = PoeModule::dispute_claim(Origin::signed(1), document.as_bytes().to_vec(), H256::repeat_byte(3));
+
+		assert_noop!(
+			PoeModule::reveal_claim(Origin::signed(1), document, salt, vec![]),
+			Error::<Test>::ClaimDisputed
+		);
+	});
+}
+
+#[test]
+fn reveal_claim_failed_when_later_claim_is_co_owned() {
+	new_test_ext().execute_with(|| {
+		let (document, salt) = commit_document();
+		// A hashed claim is longer than `MaxClaimLength`, so it is made co-owned directly
+		let _ = PoeModule::create_hashed_claim(Origin::signed(2), document, vec![], None);
+		CoOwners::<Test>::insert(document.as_bytes().to_vec(), CoOwnership { owners: vec![2, 3], threshold: 2 });
+
+		assert_noop!(
+			PoeModule::reveal_claim(Origin::signed(1), document, salt, vec![]),
+			Error::<Test>::ClaimCoOwned
+		);
+	});
+}
+
+#[test]
+fn cancel_commitment_releases_deposit() {
+	new_test_ext().execute_with(|| {
+		let commitment = claim_commitment::<BlakeTwo256>(&H256::repeat_byte(1), &H256::repeat_byte(2));
+		let _ = PoeModule::commit_claim(Origin::signed(1), commitment);
+
+		assert_noop!(
+			PoeModule::cancel_commitment(Origin::signed(2), commitment),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::cancel_commitment(Origin::signed(1), commitment));
+
+		assert_eq!(PoeModule::private_commitment(commitment), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_noop!(
+			PoeModule::cancel_commitment(Origin::signed(1), commitment),
+			Error::<Test>::CommitmentNotExist
+		);
+	});
+}
+
+#[test]
+fn disputed_claim_is_frozen() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+
+		assert_noop!(
+			PoeModule::dispute_claim(Origin::signed(1), vec![0, 1], H256::repeat_byte(1)),
+			Error::<Test>::CannotDisputeOwnClaim
+		);
+		assert_ok!(PoeModule::dispute_claim(Origin::signed(2), vec![0, 1], H256::repeat_byte(1)));
+		assert_eq!(Balances::reserved_balance(2), 10);
+
+		assert_noop!(
+			PoeModule::transfer_claim(Origin::signed(1), vec![0, 1], 3),
+			Error::<Test>::ClaimDisputed
+		);
+		assert_noop!(
+			PoeModule::revoke_claim(Origin::signed(1), vec![0, 1]),
+			Error::<Test>::ClaimDisputed
+		);
+	});
+}
+
+#[test]
+fn resolve_dispute_for_challenger_transfers_claim() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+		let _ = PoeModule::dispute_claim(Origin::signed(2), vec![0, 1], H256::repeat_byte(1));
+
+		assert_noop!(
+			PoeModule::resolve_dispute(Origin::signed(1), vec![0, 1], true),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(PoeModule::resolve_dispute(Origin::ROOT, vec![0, 1], true));
+
+		assert_eq!(PoeModule::proofs(vec![0, 1]).map(|info| info.owner), Some(2));
+		assert_eq!(PoeModule::dispute(vec![0, 1]), None);
+		assert_eq!(Balances::reserved_balance(2), 4);
+		assert_eq!(Balances::free_balance(2), 100);
+	});
+}
+
+#[test]
+fn resolve_dispute_for_owner_slashes_bond() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+		let _ = PoeModule::dispute_claim(Origin::signed(2), vec![0, 1], H256::repeat_byte(1));
+
+		assert_ok!(PoeModule::resolve_dispute(Origin::ROOT, vec![0, 1], false));
+
+		assert_eq!(PoeModule::proofs(vec![0, 1]).map(|info| info.owner), Some(1));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 90);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), vec![0, 1], 3));
+	});
+}
+
+#[test]
+fn proof_storage_key_reads_claim_info() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone(), vec![], None, None, vec![]);
+
+		assert_eq!(unhashed::get(&proof_storage_key::<Test>(&claim)), PoeModule::proofs(&claim));
+	});
+}
+
+#[test]
+fn create_co_owned_claim_checks_threshold() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_co_owned_claim(Origin::signed(1), vec![0, 1], vec![], vec![2, 2], 3),
+			Error::<Test>::InvalidThreshold
+		);
+		assert_noop!(
+			PoeModule::create_co_owned_claim(Origin::signed(1), vec![0, 1], vec![], vec![2, 3, 4], 2),
+			Error::<Test>::TooManyCoOwners
+		);
+		assert_ok!(PoeModule::create_co_owned_claim(Origin::signed(1), vec![0, 1], vec![], vec![2, 1, 3], 2));
+
+		assert_eq!(PoeModule::co_owners(vec![0, 1]).map(|c| c.owners), Some(vec![1, 2, 3]));
+	});
+}
+
+#[test]
+fn co_owned_claim_transfers_at_threshold() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_co_owned_claim(Origin::signed(1), claim.clone(), vec![], vec![2, 3], 2);
+		assert_eq!(owned_by(2), vec![claim.clone()]);
+
+		assert_noop!(
+			PoeModule::transfer_claim(Origin::signed(4), claim.clone(), 4),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(2), claim.clone(), 3));
+		assert_eq!(PoeModule::proposal(&claim).map(|p| p.action), Some(ClaimAction::Transfer(3)));
+		assert_eq!(PoeModule::proofs(&claim).map(|info| info.owner), Some(1));
+
+		assert_noop!(
+			PoeModule::revoke_claim(Origin::signed(1), claim.clone()),
+			Error::<Test>::ProposalPending
+		);
+		assert_noop!(
+			PoeModule::approve_proposal(Origin::signed(2), claim.clone()),
+			Error::<Test>::AlreadyApproved
+		);
+		assert_ok!(PoeModule::approve_proposal(Origin::signed(3), claim.clone()));
+
+		assert_eq!(PoeModule::proofs(&claim).map(|info| info.owner), Some(3));
+		assert_eq!(PoeModule::co_owners(&claim), None);
+		assert_eq!(PoeModule::proposal(&claim), None);
+		assert_eq!(Balances::reserved_balance(3), 4);
+		assert!(owned_by(1).is_empty());
+		assert!(owned_by(2).is_empty());
+		assert_eq!(owned_by(3), vec![claim]);
+	});
+}
+
+#[test]
+fn co_owned_claim_revokes_at_threshold() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_co_owned_claim(Origin::signed(1), claim.clone(), vec![], vec![2], 2);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), claim.clone()));
+		assert!(PoeModule::proofs(&claim).is_some());
+		assert_ok!(PoeModule::approve_proposal(Origin::signed(1), claim.clone()));
+
+		assert_eq!(PoeModule::proofs(&claim), None);
+		assert_eq!(PoeModule::co_owners(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(owned_by(2).is_empty());
+	});
+}
+
+#[test]
+fn expired_proposal_cannot_be_approved() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_co_owned_claim(Origin::signed(1), claim.clone(), vec![], vec![2], 2);
+		let _ = PoeModule::revoke_claim(Origin::signed(1), claim.clone());
+
+		System::set_block_number(10);
+		assert_noop!(
+			PoeModule::approve_proposal(Origin::signed(2), claim.clone()),
+			Error::<Test>::ProposalExpired
+		);
+
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(2), claim.clone(), 3));
+		assert_eq!(PoeModule::proposal(&claim).map(|p| p.proposer), Some(2));
+
+		assert_noop!(
+			PoeModule::cancel_proposal(Origin::signed(1), claim.clone()),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::cancel_proposal(Origin::signed(2), claim.clone()));
+		assert_eq!(PoeModule::proposal(&claim), None);
+	});
+}
+
+#[test]
+fn expired_proposal_can_be_cancelled_by_anyone() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_co_owned_claim(Origin::signed(1), claim.clone(), vec![], vec![2], 2);
+		let _ = PoeModule::revoke_claim(Origin::signed(1), claim.clone());
+
+		assert_noop!(
+			PoeModule::cancel_proposal(Origin::signed(3), claim.clone()),
+			Error::<Test>::NotClaimOwner
+		);
+
+		System::set_block_number(10);
+		assert_ok!(PoeModule::cancel_proposal(Origin::signed(3), claim.clone()));
+		assert_eq!(PoeModule::proposal(&claim), None);
+	});
+}
+
+#[test]
+fn transfer_claim_records_custody_history() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		System::set_block_number(1);
+		Timestamp::set_timestamp(10);
+		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone(), vec![], None, None, vec![]);
+
+		System::set_block_number(2);
+		Timestamp::set_timestamp(20);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+
+		assert_eq!(PoeModule::claim_history(&claim), vec![
+			CustodyRecord { owner: 1, block_number: 1, moment: 10 },
+			CustodyRecord { owner: 2, block_number: 2, moment: 20 },
+		]);
+		assert!(System::events().iter().any(|record| {
+			record.event == TestEvent::poe(RawEvent::ClaimTransferred(1, 2, claim.clone()))
+		}));
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), claim.clone()));
+		assert_eq!(PoeModule::claim_history(&claim), vec![]);
+	});
+}
+
+#[test]
+fn custody_history_drops_oldest_records() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone(), vec![], None, None, vec![]);
+
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(2), claim.clone(), 1));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+
+		let owners: Vec<u64> = PoeModule::claim_history(&claim).into_iter().map(|r| r.owner).collect();
+		assert_eq!(owners, vec![2, 1, 2]);
+	});
+}
+
+#[test]
+fn create_category_requires_category_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_category(Origin::signed(1), b"contract".to_vec()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PoeModule::create_category(Origin::ROOT, b"contracts".to_vec()),
+			Error::<Test>::CategoryNameTooLong
+		);
+		assert_ok!(PoeModule::create_category(Origin::ROOT, b"contract".to_vec()));
+
+		assert_eq!(PoeModule::category(0), Some(b"contract".to_vec()));
+		assert_eq!(PoeModule::next_category_id(), 1);
+	});
+}
+
+#[test]
+fn create_claim_with_category_and_tags() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_category(Origin::ROOT, b"image".to_vec());
+
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, Some(1), vec![]),
+			Error::<Test>::CategoryNotExist
+		);
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, Some(0), vec![vec![1]; 3]),
+			Error::<Test>::TooManyTags
+		);
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, Some(0), vec![vec![1; 5]]),
+			Error::<Test>::TagTooLong
+		);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, Some(0), vec![b"png".to_vec()]));
+		assert_ok!(PoeModule::create_claim(Origin::signed(2), vec![0, 2], vec![], None, Some(0), vec![]));
+
+		assert_eq!(PoeModule::claim_category(vec![0, 1]), Some(0));
+		assert_eq!(PoeModule::claim_tags(vec![0, 1]), vec![b"png".to_vec()]);
+		// The base, the claim, its entry in the category index and its tags
+		assert_eq!(Balances::reserved_balance(1), 2 + 2 + 2 + 3);
+		assert_eq!(Balances::reserved_balance(2), 2 + 2 + 2);
+		let mut claims: Vec<Vec<u8>> = PoeModule::claims_in_category(0, 0, 10).into_iter().map(|(c, _)| c).collect();
+		claims.sort();
+		assert_eq!(claims, vec![vec![0, 1], vec![0, 2]]);
+		assert_eq!(PoeModule::claims_in_category(0, 1, 10).len(), 1);
+	});
+}
+
+#[test]
+fn category_in_use_cannot_be_removed() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_category(Origin::ROOT, b"code".to_vec());
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, Some(0), vec![b"rs".to_vec()]);
+
+		assert_noop!(PoeModule::remove_category(Origin::ROOT, 0), Error::<Test>::CategoryInUse);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), vec![0, 1]));
+		assert_eq!(PoeModule::claim_tags(vec![0, 1]), Vec::<Vec<u8>>::new());
+
+		assert_ok!(PoeModule::remove_category(Origin::ROOT, 0));
+		assert_eq!(PoeModule::category(0), None);
+		assert_noop!(PoeModule::remove_category(Origin::ROOT, 0), Error::<Test>::CategoryNotExist);
+	});
+}
+
+#[test]
+fn wrapped_claim_is_locked() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone(), vec![], None, None, vec![]);
+
+		assert_noop!(PoeModule::wrap_claim(Origin::signed(2), claim.clone()), Error::<Test>::NotClaimOwner);
+		assert_ok!(PoeModule::wrap_claim(Origin::signed(1), claim.clone()));
+		assert_eq!(PoeModule::wrapped_claim(&claim), Some(0));
+		assert_eq!(PoeModule::token_owner(0), Some(1));
+
+		assert_noop!(PoeModule::wrap_claim(Origin::signed(1), claim.clone()), Error::<Test>::ClaimIsWrapped);
+		assert_noop!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2), Error::<Test>::ClaimIsWrapped);
+		assert_noop!(PoeModule::revoke_claim(Origin::signed(1), claim.clone()), Error::<Test>::ClaimIsWrapped);
+	});
+}
+
+#[test]
+fn bought_token_unwraps_to_buyer() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone(), vec![], None, None, vec![]);
+		let _ = PoeModule::wrap_claim(Origin::signed(1), claim.clone());
+
+		assert_noop!(PoeModule::buy_token(Origin::signed(2), 0, 50), Error::<Test>::TokenNotForSale);
+		assert_noop!(PoeModule::ask_token(Origin::signed(2), 0, Some(50)), Error::<Test>::NotTokenOwner);
+		assert_ok!(PoeModule::ask_token(Origin::signed(1), 0, Some(50)));
+		assert_noop!(PoeModule::buy_token(Origin::signed(2), 0, 40), Error::<Test>::PriceTooLow);
+		assert_ok!(PoeModule::buy_token(Origin::signed(2), 0, 50));
+
+		assert_eq!(PoeModule::token_owner(0), Some(2));
+		assert_eq!(PoeModule::token_price(0), None);
+		assert_eq!(Balances::free_balance(1), 146);
+		assert_eq!(PoeModule::proofs(&claim).map(|info| info.owner), Some(1));
+
+		assert_noop!(PoeModule::unwrap_claim(Origin::signed(1), 0), Error::<Test>::NotTokenOwner);
+		assert_ok!(PoeModule::unwrap_claim(Origin::signed(2), 0));
+
+		assert_eq!(PoeModule::proofs(&claim).map(|info| info.owner), Some(2));
+		assert_eq!(Balances::reserved_balance(2), 4);
+		assert_eq!(PoeModule::wrapped_claim(&claim), None);
+		assert_eq!(PoeModule::token_owner(0), None);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(2), claim, 1));
+	});
+}
+
+#[test]
+fn transfer_token_delists_it() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+		let _ = PoeModule::wrap_claim(Origin::signed(1), vec![0, 1]);
+		let _ = PoeModule::ask_token(Origin::signed(1), 0, Some(50));
+
+		assert_ok!(PoeModule::transfer_token(Origin::signed(1), 3, 0));
+
+		assert_eq!(PoeModule::token_owner(0), Some(3));
+		assert_eq!(PoeModule::token_price(0), None);
+	});
+}
+
+#[test]
+fn wrapped_claim_cannot_be_disputed() {
+	new_test_ext().execute_with(|| {
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], None, None, vec![]);
+		let _ = PoeModule::wrap_claim(Origin::signed(1), vec![0, 1]);
+		let _ = PoeModule::transfer_token(Origin::signed(1), 2, 0);
+
+		assert_noop!(
+			PoeModule::dispute_claim(Origin::signed(3), vec![0, 1], H256::repeat_byte(1)),
+			Error::<Test>::ClaimIsWrapped
+		);
+		assert_noop!(
+			PoeModule::dispute_claim(Origin::signed(2), vec![0, 1], H256::repeat_byte(1)),
+			Error::<Test>::ClaimIsWrapped
+		);
+	});
+}
+
+#[test]
+fn wrapped_claim_is_renewed_by_token_holder() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let _ = PoeModule::create_claim(Origin::signed(1), vec![0, 1], vec![], Some(3), None, vec![]);
+		let _ = PoeModule::wrap_claim(Origin::signed(1), vec![0, 1]);
+		let _ = PoeModule::transfer_token(Origin::signed(1), 2, 0);
+
+		assert_noop!(
+			PoeModule::renew_claim(Origin::signed(1), vec![0, 1], 10),
+			Error::<Test>::NotTokenOwner
+		);
+		assert_ok!(PoeModule::renew_claim(Origin::signed(2), vec![0, 1], 10));
+		assert_eq!(Balances::free_balance(2), 100 - 5);
+
+		for block in 2..=4 {
+			PoeModule::on_initialize(block);
+		}
+		assert!(PoeModule::proofs(vec![0, 1]).is_some());
+		assert_eq!(PoeModule::token_owner(0), Some(2));
+
+		for block in 5..=14 {
+			PoeModule::on_initialize(block);
+		}
+		assert_eq!(PoeModule::proofs(vec![0, 1]), None);
+		assert_eq!(PoeModule::token_owner(0), None);
+	});
+}
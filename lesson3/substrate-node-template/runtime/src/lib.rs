@@ -260,6 +260,19 @@ impl template::Trait for Runtime {
 // 附加题答案
 parameter_types! {
 	pub const MaxClaimLength: u32 = 6;
+	pub const MaxMemoLength: u32 = 256;
+	pub const ClaimDepositBase: Balance = 1_000;
+	pub const ClaimDepositPerByte: Balance = 10;
+	pub const RenewalFee: Balance = 1_000;
+	pub const MaxExpiriesPerBlock: u32 = 50;
+	pub const MaxAttestations: u32 = 20;
+	pub const DisputeBond: Balance = 10_000;
+	pub const MaxCoOwners: u32 = 16;
+	pub const ProposalLifetime: BlockNumber = 7 * DAYS;
+	pub const MaxHistoryLength: u32 = 100;
+	pub const MaxCategoryNameLength: u32 = 32;
+	pub const MaxTags: u32 = 8;
+	pub const MaxTagLength: u32 = 32;
 }
 
 impl poe::Trait for Runtime {
@@ -267,6 +280,22 @@ impl poe::Trait for Runtime {
 	
 	// 附加题答案
 	type MaxClaimLength = MaxClaimLength;
+	type MaxMemoLength = MaxMemoLength;
+	type Currency = Balances;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
+	type RenewalFee = RenewalFee;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxAttestations = MaxAttestations;
+	type DisputeBond = DisputeBond;
+	type ArbitratorOrigin = system::EnsureRoot<AccountId>;
+	type MaxCoOwners = MaxCoOwners;
+	type ProposalLifetime = ProposalLifetime;
+	type MaxHistoryLength = MaxHistoryLength;
+	type CategoryOrigin = system::EnsureRoot<AccountId>;
+	type MaxCategoryNameLength = MaxCategoryNameLength;
+	type MaxTags = MaxTags;
+	type MaxTagLength = MaxTagLength;
 }
 
 construct_runtime!(
@@ -285,7 +314,7 @@ construct_runtime!(
 		Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Used for the module template in `./template.rs`
 		TemplateModule: template::{Module, Call, Storage, Event<T>},
-		PoeModule: poe::{Module, Call, Storage, Event<T>},
+		PoeModule: poe::{Module, Call, Storage, Event<T>, Config},
 	}
 );
 
@@ -399,6 +428,28 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl poe_runtime_api::PoeApi<Block, AccountId, BlockNumber, u64, Hash> for Runtime {
+		fn claims_of(account: AccountId, cursor: u32, limit: u32) -> Vec<(Vec<u8>, poe::ClaimInfo<AccountId, BlockNumber, u64>)> {
+			PoeModule::claims_of(&account, cursor, limit)
+		}
+
+		fn attestations_of(claim: Vec<u8>) -> Vec<(AccountId, poe::Attestation<Hash, BlockNumber>)> {
+			PoeModule::attestations_of(&claim)
+		}
+
+		fn verify_inclusion(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool {
+			PoeModule::verify_batch_inclusion(root, leaf, proof)
+		}
+
+		fn custody_history(claim: Vec<u8>) -> Vec<poe::CustodyRecord<AccountId, BlockNumber, u64>> {
+			PoeModule::claim_history(claim)
+		}
+
+		fn claims_in_category(category: poe::CategoryId, cursor: u32, limit: u32) -> Vec<(Vec<u8>, poe::ClaimInfo<AccountId, BlockNumber, u64>)> {
+			PoeModule::claims_in_category(category, cursor, limit)
+		}
+	}
+
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
 		fn grandpa_authorities() -> GrandpaAuthorityList {
 			Grandpa::grandpa_authorities()
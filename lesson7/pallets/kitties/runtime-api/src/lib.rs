@@ -0,0 +1,22 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+pub use pallet_kitties::{MarketStats, SaleRecord};
+
+sp_api::decl_runtime_apis! {
+	/// Market data of the kitties pallet.
+	pub trait KittiesApi<KittyIndex, AccountId, Balance, BlockNumber> where
+		KittyIndex: Codec,
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Recent sales of a kitty, oldest first.
+		fn price_history(kitty_id: KittyIndex) -> Vec<SaleRecord<AccountId, Balance, BlockNumber>>;
+		/// Running statistics of all sales.
+		fn market_stats() -> MarketStats<KittyIndex, AccountId, Balance, BlockNumber>;
+		/// Lowest price among the kitties currently for sale.
+		fn floor_price() -> Option<Balance>;
+	}
+}
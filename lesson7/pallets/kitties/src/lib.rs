@@ -2,29 +2,93 @@
 
 use codec::{Encode, Decode};
 use frame_support::{
-	decl_module, decl_storage, decl_error, decl_event, ensure, StorageValue, StorageMap, Parameter,
-	traits::{Randomness, Currency, ExistenceRequirement},
+	decl_module, decl_storage, decl_error, decl_event, ensure, StorageValue, StorageMap, IterableStorageMap, Parameter,
+	traits::{Randomness, Currency, ReservableCurrency, ExistenceRequirement, LockIdentifier, Get},
+	weights::Weight,
 };
 use sp_io::hashing::blake2_128;
 use frame_system::{self as system, ensure_signed};
-use sp_runtime::{DispatchError, traits::{AtLeast32Bit, Bounded, Member}};
+use sp_runtime::{DispatchError, DispatchResult, SaturatedConversion, traits::{AtLeast32Bit, Bounded, Member, Hash, Saturating, Zero}};
+use sp_std::prelude::*;
+use sp_std::cmp::Ordering;
 use crate::linked_item::{LinkedList, LinkedItem};
+use crate::price_heap::PriceHeap;
+use unique_assets::UniqueAssets;
 
 mod linked_item;
+mod price_heap;
 
 #[derive(Encode, Decode)]
 pub struct Kitty(pub [u8; 16]);
 
+/// A pending commitment of a commit-reveal kitty creation.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode)]
+pub struct Commitment<Hash, BlockNumber, Balance> {
+	/// Hash of the secret to be revealed.
+	pub hash: Hash,
+	/// Block the commitment was made in.
+	pub block: BlockNumber,
+	/// Amount reserved until the secret is revealed.
+	pub deposit: Balance,
+}
+
+/// A sale of a kitty.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode, Clone)]
+pub struct SaleRecord<AccountId, Balance, BlockNumber> {
+	/// Block the sale happened in.
+	pub block: BlockNumber,
+	pub price: Balance,
+	pub buyer: AccountId,
+	pub seller: AccountId,
+}
+
+/// Running statistics of all kitty sales.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode, Clone, Default)]
+pub struct MarketStats<KittyIndex, AccountId, Balance, BlockNumber> {
+	/// Sum of all sale prices.
+	pub volume: Balance,
+	/// Number of sales.
+	pub trade_count: u64,
+	/// The latest sale and the kitty sold.
+	pub last_sale: Option<(KittyIndex, SaleRecord<AccountId, Balance, BlockNumber>)>,
+}
+
+/// Lets other pallets lock a kitty, e.g. to use it as collateral.
+/// A locked kitty cannot be transferred, sold, bred or burned.
+pub trait KittyLock<KittyIndex> {
+	/// Lock a kitty for the given reason.
+	fn lock(kitty_id: KittyIndex, reason: LockIdentifier) -> DispatchResult;
+	/// Remove the lock with the given reason.
+	fn unlock(kitty_id: KittyIndex, reason: LockIdentifier) -> DispatchResult;
+	/// Whether any lock exists on the kitty.
+	fn is_locked(kitty_id: KittyIndex) -> bool;
+}
+
 pub trait Trait: frame_system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 	type KittyIndex: Parameter + Member + AtLeast32Bit + Bounded + Default + Copy;
-	type Currency: Currency<Self::AccountId>;
+	type Currency: ReservableCurrency<Self::AccountId>;
 	type Randomness: Randomness<Self::Hash>;
+	/// Whether `create`, `breed` and `fuse` need a commit-reveal round instead of `Randomness`.
+	type UseCommitReveal: Get<bool>;
+	/// Amount reserved by `commit`, lost when the reveal is late.
+	type CommitDeposit: Get<BalanceOf<Self>>;
+	/// Number of blocks after the commitment in which the secret must be revealed.
+	type RevealPeriod: Get<Self::BlockNumber>;
+	/// Number of sales kept in the price history of each kitty.
+	type MaxPriceHistory: Get<u32>;
 }
 
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type CommitmentOf<T> = Commitment<<T as system::Trait>::Hash, <T as system::Trait>::BlockNumber, BalanceOf<T>>;
+type SaleRecordOf<T> = SaleRecord<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+type MarketStatsOf<T> = MarketStats<<T as Trait>::KittyIndex, <T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
 type KittyLinkedItem<T> = LinkedItem<<T as Trait>::KittyIndex>;
 type OwnedKittiesList<T> = LinkedList<OwnedKitties<T>, <T as system::Trait>::AccountId, <T as Trait>::KittyIndex>;
+type ListedKittiesHeap<T> = PriceHeap<ListedKitties<T>, ListedKittyPositions<T>, ListedKittiesCount, BalanceOf<T>, <T as Trait>::KittyIndex>;
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Kitties {
@@ -32,6 +96,8 @@ decl_storage! {
 		pub Kitties get(fn kitties): map hasher(blake2_128_concat) T::KittyIndex => Option<Kitty>;
 		/// Stores the total number of kitties. i.e. the next kitty index
 		pub KittiesCount get(fn kitties_count): T::KittyIndex;
+		/// Stores the number of kitties not burned yet.
+		pub KittiesSupply get(fn kitties_supply): T::KittyIndex;
 
 		/// Store owned kitties in a linked list.
 		pub OwnedKitties get(fn owned_kitties): map hasher(blake2_128_concat) (T::AccountId, Option<T::KittyIndex>) => Option<KittyLinkedItem<T>>;
@@ -40,6 +106,25 @@ decl_storage! {
 
 		/// Get kitty price. None means not for sale.
 		pub KittyPrices get(fn kitty_price): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+		/// The kitties for sale as a min-heap on price, to keep the floor price at hand.
+		ListedKitties: map hasher(twox_64_concat) u32 => Option<(BalanceOf<T>, T::KittyIndex)>;
+		/// Position of each kitty for sale in `ListedKitties`.
+		ListedKittyPositions: map hasher(blake2_128_concat) T::KittyIndex => Option<u32>;
+		/// Number of kitties for sale.
+		pub ListedKittiesCount get(fn listed_kitties_count): u32;
+		/// Latest sales of each kitty, oldest first. At most `MaxPriceHistory` entries.
+		pub PriceHistory get(fn price_history): map hasher(blake2_128_concat) T::KittyIndex => Vec<SaleRecordOf<T>>;
+		/// Running statistics of all sales.
+		pub Stats get(fn market_stats): MarketStatsOf<T>;
+
+		/// Number of fusions a kitty descends from. Zero for created and bred kitties.
+		pub KittyFusionLevels get(fn kitty_fusion_level): map hasher(blake2_128_concat) T::KittyIndex => u32;
+
+		/// Reasons a kitty is locked for. Empty means unlocked.
+		pub KittyLocks get(fn kitty_locks): map hasher(blake2_128_concat) T::KittyIndex => Vec<LockIdentifier>;
+
+		/// Pending commit-reveal commitment of each account.
+		pub Commitments get(fn commitment): map hasher(blake2_128_concat) T::AccountId => Option<CommitmentOf<T>>;
 	}
 }
 
@@ -51,12 +136,23 @@ decl_error! {
 		RequireOwner,
 		NotForSale,
 		PriceTooLow,
+		KittyForSale,
+		KittyLocked,
+		AlreadyLocked,
+		NotLocked,
+		CommitRevealRequired,
+		CommitRevealDisabled,
+		AlreadyCommitted,
+		NoCommitment,
+		RevealTooEarly,
+		InvalidReveal,
 	}
 }
 
 decl_event!(
 	pub enum Event<T> where
 		<T as frame_system::Trait>::AccountId,
+		<T as frame_system::Trait>::Hash,
 		<T as Trait>::KittyIndex,
 		Balance = BalanceOf<T>,
 	{
@@ -68,6 +164,18 @@ decl_event!(
 		Ask(AccountId, KittyIndex, Option<Balance>),
 		/// A kitty is sold. (from, to, kitty_id, price)
 		Sold(AccountId, AccountId, KittyIndex, Balance),
+		/// Two kitties are fused into a new one. (owner, kitty_id_1, kitty_id_2, new_kitty_id)
+		Fused(AccountId, KittyIndex, KittyIndex, KittyIndex),
+		/// A kitty is burned. (owner, kitty_id)
+		Burned(AccountId, KittyIndex),
+		/// A kitty is locked. (kitty_id, reason)
+		Locked(KittyIndex, LockIdentifier),
+		/// A kitty lock is removed. (kitty_id, reason)
+		Unlocked(KittyIndex, LockIdentifier),
+		/// A secret is committed. (owner, commitment)
+		Committed(AccountId, Hash),
+		/// A secret is revealed too late and the deposit is slashed. (owner, deposit)
+		CommitmentSlashed(AccountId, Balance),
 	}
 );
 
@@ -77,18 +185,29 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		/// `KittiesSupply` was added after kitties were minted, it is counted from `Kitties` on
+		/// chains where it is still unset.
+		fn on_runtime_upgrade() -> Weight {
+			if !Self::kitties_supply().is_zero() || Self::kitties_count().is_zero() {
+				return T::DbWeight::get().reads(2);
+			}
+
+			let supply = Kitties::<T>::iter().count() as u32;
+			KittiesSupply::<T>::put(T::KittyIndex::from(supply));
+
+			T::DbWeight::get().reads_writes(2 + supply as Weight, 1)
+		}
+
 		/// Create a new kitty
 		#[weight = 0]
 		pub fn create(origin) {
 			let sender = ensure_signed(origin)?;
-			let kitty_id = Self::next_kitty_id()?;
+			ensure!(!T::UseCommitReveal::get(), Error::<T>::CommitRevealRequired);
 
 			// Generate a random 128bit value
 			let dna = Self::random_value(&sender);
 
-			// Create and store kitty
-			let kitty = Kitty(dna);
-			Self::insert_kitty(&sender, kitty_id, kitty);
+			let kitty_id = Self::do_create(&sender, dna)?;
 
 			Self::deposit_event(RawEvent::Created(sender, kitty_id));
 		}
@@ -97,18 +216,98 @@ decl_module! {
 		#[weight = 0]
 		pub fn breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
 			let sender = ensure_signed(origin)?;
+			ensure!(!T::UseCommitReveal::get(), Error::<T>::CommitRevealRequired);
 
-			let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2)?;
+			// Generate a random 128bit value
+			let selector = Self::random_value(&sender);
+
+			let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2, selector)?;
 
 			Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
 		}
 
+		/// Commit the hash of a secret, to be revealed by `reveal_create`, `reveal_breed` or `reveal_fuse`
+		/// in a later block within `RevealPeriod`
+		#[weight = 0]
+		pub fn commit(origin, hash: T::Hash) {
+			let sender = ensure_signed(origin)?;
+			ensure!(T::UseCommitReveal::get(), Error::<T>::CommitRevealDisabled);
+			ensure!(!<Commitments<T>>::contains_key(&sender), Error::<T>::AlreadyCommitted);
+
+			let deposit = T::CommitDeposit::get();
+			T::Currency::reserve(&sender, deposit)?;
+
+			<Commitments<T>>::insert(&sender, Commitment {
+				hash,
+				block: <frame_system::Module<T>>::block_number(),
+				deposit,
+			});
+
+			Self::deposit_event(RawEvent::Committed(sender, hash));
+		}
+
+		/// Reveal a committed secret to create a new kitty
+		#[weight = 0]
+		pub fn reveal_create(origin, secret: T::Hash) {
+			let sender = ensure_signed(origin)?;
+			Self::next_kitty_id()?;
+
+			if let Some(dna) = Self::reveal(&sender, secret)? {
+				let kitty_id = Self::do_create(&sender, dna)?;
+
+				Self::deposit_event(RawEvent::Created(sender, kitty_id));
+			}
+		}
+
+		/// Reveal a committed secret to breed kitties
+		#[weight = 0]
+		pub fn reveal_breed(origin, secret: T::Hash, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_can_breed(&sender, kitty_id_1, kitty_id_2)?;
+			Self::next_kitty_id()?;
+
+			if let Some(selector) = Self::reveal(&sender, secret)? {
+				let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2, selector)?;
+
+				Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
+			}
+		}
+
+		/// Fuse kitties, both inputs are consumed
+		#[weight = 0]
+		pub fn fuse(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			ensure!(!T::UseCommitReveal::get(), Error::<T>::CommitRevealRequired);
+
+			// Generate a random 128bit value
+			let selector = Self::random_value(&sender);
+
+			let new_kitty_id = Self::do_fuse(&sender, kitty_id_1, kitty_id_2, selector)?;
+
+			Self::deposit_event(RawEvent::Fused(sender, kitty_id_1, kitty_id_2, new_kitty_id));
+		}
+
+		/// Reveal a committed secret to fuse kitties
+		#[weight = 0]
+		pub fn reveal_fuse(origin, secret: T::Hash, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_can_fuse(&sender, kitty_id_1, kitty_id_2)?;
+			Self::next_kitty_id()?;
+
+			if let Some(selector) = Self::reveal(&sender, secret)? {
+				let new_kitty_id = Self::do_fuse(&sender, kitty_id_1, kitty_id_2, selector)?;
+
+				Self::deposit_event(RawEvent::Fused(sender, kitty_id_1, kitty_id_2, new_kitty_id));
+			}
+		}
+
 		/// Transfer a kitty to new owner
 		#[weight = 0]
 		pub fn transfer(origin, to: T::AccountId, kitty_id: T::KittyIndex) {
 			let sender = ensure_signed(origin)?;
 
 			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			Self::ensure_unlocked(kitty_id)?;
 
 			Self::do_transfer(&sender, &to, kitty_id);
 
@@ -122,8 +321,9 @@ decl_module! {
 			let sender = ensure_signed(origin)?;
 
 			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			Self::ensure_unlocked(kitty_id)?;
 
-			<KittyPrices<T>>::mutate_exists(kitty_id, |price| *price = new_price);
+			Self::set_price(kitty_id, new_price);
 
 			Self::deposit_event(RawEvent::Ask(sender, kitty_id, new_price));
 		}
@@ -137,14 +337,21 @@ decl_module! {
 
 			let kitty_price = Self::kitty_price(kitty_id).ok_or(Error::<T>::NotForSale)?;
 
+			Self::ensure_unlocked(kitty_id)?;
+
 			ensure!(price >= kitty_price, Error::<T>::PriceTooLow);
 
 			T::Currency::transfer(&sender, &owner, kitty_price, ExistenceRequirement::KeepAlive)?;
 
-			<KittyPrices<T>>::remove(kitty_id);
-
 			Self::do_transfer(&owner, &sender, kitty_id);
 
+			Self::record_sale(kitty_id, SaleRecord {
+				block: <frame_system::Module<T>>::block_number(),
+				price: kitty_price,
+				buyer: sender.clone(),
+				seller: owner.clone(),
+			});
+
 			Self::deposit_event(RawEvent::Sold(owner, sender, kitty_id, kitty_price));
 		}
 	}
@@ -154,7 +361,60 @@ fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
 	(selector & dna1) | (!selector & dna2)
 }
 
+/// How far a gene is from the most common bit pattern, i.e. four bits set.
+fn gene_rarity(dna: u8) -> u32 {
+	(dna.count_ones() as i32 - 4).abs() as u32
+}
+
+/// Keep the rarer gene, fall back to the random selector on a tie.
+fn fuse_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
+	match gene_rarity(dna1).cmp(&gene_rarity(dna2)) {
+		Ordering::Greater => dna1,
+		Ordering::Less => dna2,
+		Ordering::Equal => combine_dna(dna1, dna2, selector),
+	}
+}
+
 impl<T: Trait> Module<T> {
+	/// Lowest price among the kitties currently for sale.
+	pub fn floor_price() -> Option<BalanceOf<T>> {
+		<ListedKittiesHeap<T>>::peek().map(|(price, _)| price)
+	}
+
+	/// List a kitty for sale at `price`, or delist it with None.
+	fn set_price(kitty_id: T::KittyIndex, price: Option<BalanceOf<T>>) {
+		match price {
+			Some(price) => {
+				<KittyPrices<T>>::insert(kitty_id, price);
+				<ListedKittiesHeap<T>>::insert(kitty_id, price);
+			}
+			None => {
+				<KittyPrices<T>>::remove(kitty_id);
+				<ListedKittiesHeap<T>>::remove(kitty_id);
+			}
+		}
+	}
+
+	fn record_sale(kitty_id: T::KittyIndex, record: SaleRecordOf<T>) {
+		<PriceHistory<T>>::mutate(kitty_id, |history| {
+			let max = T::MaxPriceHistory::get() as usize;
+			if max == 0 {
+				return;
+			}
+			// Drop the oldest sales to make room
+			while history.len() >= max {
+				history.remove(0);
+			}
+			history.push(record.clone());
+		});
+
+		<Stats<T>>::mutate(|stats| {
+			stats.volume = stats.volume.saturating_add(record.price);
+			stats.trade_count = stats.trade_count.saturating_add(1);
+			stats.last_sale = Some((kitty_id, record));
+		});
+	}
+
 	fn random_value(sender: &T::AccountId) -> [u8; 16] {
 		let payload = (
 			T::Randomness::random_seed(),
@@ -164,6 +424,34 @@ impl<T: Trait> Module<T> {
 		payload.using_encoded(blake2_128)
 	}
 
+	/// Check a revealed secret against the commitment of `sender` and release the deposit.
+	/// Returns None when the reveal is late, in which case the deposit is slashed.
+	fn reveal(sender: &T::AccountId, secret: T::Hash) -> sp_std::result::Result<Option<[u8; 16]>, DispatchError> {
+		let commitment = Self::commitment(sender).ok_or(Error::<T>::NoCommitment)?;
+		let now = <frame_system::Module<T>>::block_number();
+
+		ensure!(now > commitment.block, Error::<T>::RevealTooEarly);
+		ensure!(T::Hashing::hash_of(&secret) == commitment.hash, Error::<T>::InvalidReveal);
+
+		<Commitments<T>>::remove(sender);
+
+		if now > commitment.block + T::RevealPeriod::get() {
+			T::Currency::slash_reserved(sender, commitment.deposit);
+			Self::deposit_event(RawEvent::CommitmentSlashed(sender.clone(), commitment.deposit));
+			return Ok(None);
+		}
+
+		T::Currency::unreserve(sender, commitment.deposit);
+
+		// The secret is unknown to block authors and the commit block hash was unknown to the committer
+		let payload = (
+			secret,
+			<frame_system::Module<T>>::block_hash(commitment.block),
+			&sender,
+		);
+		Ok(Some(payload.using_encoded(blake2_128)))
+	}
+
 	fn next_kitty_id() -> sp_std::result::Result<T::KittyIndex, DispatchError> {
 		let kitty_id = Self::kitties_count();
 		if kitty_id == T::KittyIndex::max_value() {
@@ -181,25 +469,40 @@ impl<T: Trait> Module<T> {
 		// Create and store kitty
 		Kitties::<T>::insert(kitty_id, kitty);
 		KittiesCount::<T>::put(kitty_id + 1.into());
+		KittiesSupply::<T>::mutate(|supply| *supply += 1.into());
 
 		Self::insert_owned_kitty(owner, kitty_id);
 	}
 
-	fn do_breed(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+	fn do_create(sender: &T::AccountId, dna: [u8; 16]) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		let kitty_id = Self::next_kitty_id()?;
+
+		// Create and store kitty
+		Self::insert_kitty(sender, kitty_id, Kitty(dna));
+
+		Ok(kitty_id)
+	}
+
+	fn ensure_can_breed(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<(Kitty, Kitty), DispatchError> {
 		let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
 		let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
 
 		ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_1))), Error::<T>::RequireOwner);
 		ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_2))), Error::<T>::RequireOwner);
 		ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RequireDifferentParent);
+		Self::ensure_unlocked(kitty_id_1)?;
+		Self::ensure_unlocked(kitty_id_2)?;
+
+		Ok((kitty1, kitty2))
+	}
+
+	fn do_breed(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex, selector: [u8; 16]) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		let (kitty1, kitty2) = Self::ensure_can_breed(sender, kitty_id_1, kitty_id_2)?;
 
 		let kitty_id = Self::next_kitty_id()?;
 
 		let kitty1_dna = kitty1.0;
 		let kitty2_dna = kitty2.0;
-
-		// Generate a random 128bit value
-		let selector = Self::random_value(&sender);
 		let mut new_dna = [0u8; 16];
 
 		// Combine parents and selector to create new kitty
@@ -212,23 +515,161 @@ impl<T: Trait> Module<T> {
 		Ok(kitty_id)
 	}
 
+	fn ensure_can_fuse(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<(Kitty, Kitty), DispatchError> {
+		let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
+		let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
+
+		ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_1))), Error::<T>::RequireOwner);
+		ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_2))), Error::<T>::RequireOwner);
+		ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RequireDifferentParent);
+		ensure!(!<KittyPrices<T>>::contains_key(kitty_id_1), Error::<T>::KittyForSale);
+		ensure!(!<KittyPrices<T>>::contains_key(kitty_id_2), Error::<T>::KittyForSale);
+		Self::ensure_unlocked(kitty_id_1)?;
+		Self::ensure_unlocked(kitty_id_2)?;
+
+		Ok((kitty1, kitty2))
+	}
+
+	fn do_fuse(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex, selector: [u8; 16]) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		let (kitty1, kitty2) = Self::ensure_can_fuse(sender, kitty_id_1, kitty_id_2)?;
+
+		let kitty_id = Self::next_kitty_id()?;
+
+		let kitty1_dna = kitty1.0;
+		let kitty2_dna = kitty2.0;
+		let mut new_dna = [0u8; 16];
+
+		// Keep the rarer gene of each pair
+		for i in 0..kitty1_dna.len() {
+			new_dna[i] = fuse_dna(kitty1_dna[i], kitty2_dna[i], selector[i]);
+		}
+
+		let fusion_level = Self::kitty_fusion_level(kitty_id_1)
+			.max(Self::kitty_fusion_level(kitty_id_2))
+			.saturating_add(1);
+
+		Self::remove_kitty(sender, kitty_id_1);
+		Self::remove_kitty(sender, kitty_id_2);
+
+		Self::insert_kitty(sender, kitty_id, Kitty(new_dna));
+		<KittyFusionLevels<T>>::insert(kitty_id, fusion_level);
+
+		Ok(kitty_id)
+	}
+
+	fn ensure_unlocked(kitty_id: T::KittyIndex) -> DispatchResult {
+		ensure!(!<Self as KittyLock<T::KittyIndex>>::is_locked(kitty_id), Error::<T>::KittyLocked);
+		Ok(())
+	}
+
+	fn remove_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+		<OwnedKittiesList<T>>::remove(owner, kitty_id);
+		<KittyOwners<T>>::remove(kitty_id);
+		Self::set_price(kitty_id, None);
+		<KittyFusionLevels<T>>::remove(kitty_id);
+		<PriceHistory<T>>::remove(kitty_id);
+		Kitties::<T>::remove(kitty_id);
+		KittiesSupply::<T>::mutate(|supply| *supply = supply.saturating_sub(1.into()));
+	}
+
 	fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex)  {
+		// The new owner has not asked for the old price
+		Self::set_price(kitty_id, None);
 		<OwnedKittiesList<T>>::remove(&from, kitty_id);
 		Self::insert_owned_kitty(&to, kitty_id);
 	}
 }
 
+impl<T: Trait> KittyLock<T::KittyIndex> for Module<T> {
+	fn lock(kitty_id: T::KittyIndex, reason: LockIdentifier) -> DispatchResult {
+		ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyId);
+
+		let mut locks = Self::kitty_locks(kitty_id);
+		ensure!(!locks.contains(&reason), Error::<T>::AlreadyLocked);
+		locks.push(reason);
+		<KittyLocks<T>>::insert(kitty_id, locks);
+
+		Self::deposit_event(RawEvent::Locked(kitty_id, reason));
+		Ok(())
+	}
+
+	fn unlock(kitty_id: T::KittyIndex, reason: LockIdentifier) -> DispatchResult {
+		let mut locks = Self::kitty_locks(kitty_id);
+		let index = locks.iter().position(|r| *r == reason).ok_or(Error::<T>::NotLocked)?;
+		locks.swap_remove(index);
+		if locks.is_empty() {
+			<KittyLocks<T>>::remove(kitty_id);
+		} else {
+			<KittyLocks<T>>::insert(kitty_id, locks);
+		}
+
+		Self::deposit_event(RawEvent::Unlocked(kitty_id, reason));
+		Ok(())
+	}
+
+	fn is_locked(kitty_id: T::KittyIndex) -> bool {
+		<KittyLocks<T>>::contains_key(kitty_id)
+	}
+}
+
+impl<T: Trait> UniqueAssets<T::AccountId> for Module<T> {
+	type AssetId = T::KittyIndex;
+	type AssetInfo = Kitty;
+
+	fn total_supply() -> u64 {
+		Self::kitties_supply().saturated_into()
+	}
+
+	fn owner_of(kitty_id: &T::KittyIndex) -> Option<T::AccountId> {
+		Self::kitty_owner(kitty_id)
+	}
+
+	fn assets_of(owner: &T::AccountId) -> Vec<T::KittyIndex> {
+		<OwnedKittiesList<T>>::enumerate(owner)
+	}
+
+	fn mint(owner: &T::AccountId, kitty: Kitty) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		let kitty_id = Self::next_kitty_id()?;
+
+		Self::insert_kitty(owner, kitty_id, kitty);
+
+		Self::deposit_event(RawEvent::Created(owner.clone(), kitty_id));
+		Ok(kitty_id)
+	}
+
+	fn burn(kitty_id: &T::KittyIndex) -> DispatchResult {
+		let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+		Self::ensure_unlocked(*kitty_id)?;
+
+		Self::remove_kitty(&owner, *kitty_id);
+
+		Self::deposit_event(RawEvent::Burned(owner, *kitty_id));
+		Ok(())
+	}
+
+	fn transfer(to: &T::AccountId, kitty_id: &T::KittyIndex) -> DispatchResult {
+		let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+		Self::ensure_unlocked(*kitty_id)?;
+
+		Self::do_transfer(&owner, to, *kitty_id);
+
+		Self::deposit_event(RawEvent::Transferred(owner, to.clone(), *kitty_id));
+		Ok(())
+	}
+}
+
 /// tests for this module
 #[cfg(test)]
 mod tests {
 	use super::*;
 
 	use sp_core::H256;
-	use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+	use frame_support::{impl_outer_origin, parameter_types, weights::Weight, assert_ok, assert_noop};
 	use sp_runtime::{
 		traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
 	};
 	use frame_system as system;
+	use std::cell::RefCell;
 
 	impl_outer_origin! {
 		pub enum Origin for Test {}
@@ -244,6 +685,19 @@ mod tests {
 		pub const MaximumBlockWeight: Weight = 1024;
 		pub const MaximumBlockLength: u32 = 2 * 1024;
 		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+		pub const ExistentialDeposit: u64 = 1;
+		pub const CommitDeposit: u64 = 10;
+		pub const RevealPeriod: u64 = 5;
+		pub const MaxPriceHistory: u32 = 2;
+	}
+	thread_local! {
+		static COMMIT_REVEAL: RefCell<bool> = RefCell::new(false);
+	}
+	pub struct UseCommitReveal;
+	impl Get<bool> for UseCommitReveal {
+		fn get() -> bool {
+			COMMIT_REVEAL.with(|v| *v.borrow())
+		}
 	}
 	impl system::Trait for Test {
 		type Origin = Origin;
@@ -266,19 +720,47 @@ mod tests {
 		type AvailableBlockRatio = AvailableBlockRatio;
 		type Version = ();
 		type ModuleToIndex = ();
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 	}
+	impl pallet_balances::Trait for Test {
+		type Balance = u64;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+	}
 	impl Trait for Test {
+		type Event = ();
 		type KittyIndex = u32;
+		type Currency = pallet_balances::Module<Test>;
+		type Randomness = pallet_randomness_collective_flip::Module<Test>;
+		type UseCommitReveal = UseCommitReveal;
+		type CommitDeposit = CommitDeposit;
+		type RevealPeriod = RevealPeriod;
+		type MaxPriceHistory = MaxPriceHistory;
 	}
+	type System = system::Module<Test>;
+	type Balances = pallet_balances::Module<Test>;
+	type Kitties = Module<Test>;
 	type OwnedKittiesTest = OwnedKitties<Test>;
 
 	// This function basically just builds a genesis storage key/value store according to
 	// our desired mockup.
 	fn new_test_ext() -> sp_io::TestExternalities {
-		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100)],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	fn new_commit_reveal_test_ext() -> sp_io::TestExternalities {
+		COMMIT_REVEAL.with(|v| *v.borrow_mut() = true);
+		let mut ext = new_test_ext();
+		ext.execute_with(|| System::set_block_number(1));
+		ext
 	}
 
 	#[test]
@@ -393,4 +875,325 @@ mod tests {
 			assert_eq!(OwnedKittiesTest::get(&(0, Some(2))), None);
 		});
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn fuse_consumes_both_kitties() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+
+			assert_ok!(Kitties::fuse(Origin::signed(1), 0, 1));
+
+			assert_eq!(Kitties::kitties_count(), 3);
+			assert!(Kitties::kitties(0).is_none());
+			assert!(Kitties::kitties(1).is_none());
+			assert_eq!(Kitties::kitty_owner(0), None);
+			assert_eq!(Kitties::kitty_owner(1), None);
+			assert_eq!(Kitties::kitty_owner(2), Some(1));
+			assert_eq!(Kitties::kitty_fusion_level(2), 1);
+
+			assert_eq!(OwnedKittiesTest::get(&(1, None)), Some(KittyLinkedItem {
+				prev: Some(2),
+				next: Some(2),
+			}));
+			assert_eq!(OwnedKittiesTest::get(&(1, Some(0))), None);
+			assert_eq!(OwnedKittiesTest::get(&(1, Some(1))), None);
+		});
+	}
+
+	#[test]
+	fn fuse_increments_fusion_level() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+
+			assert_ok!(Kitties::fuse(Origin::signed(1), 0, 1));
+			assert_ok!(Kitties::fuse(Origin::signed(1), 2, 3));
+
+			assert_eq!(Kitties::kitty_fusion_level(4), 2);
+		});
+	}
+
+	#[test]
+	fn fuse_fails_for_listed_kitty() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 1, Some(10)));
+
+			assert_noop!(Kitties::fuse(Origin::signed(1), 0, 1), Error::<Test>::KittyForSale);
+		});
+	}
+
+	#[test]
+	fn fuse_requires_owner() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(2)));
+
+			assert_noop!(Kitties::fuse(Origin::signed(1), 0, 1), Error::<Test>::RequireOwner);
+		});
+	}
+
+	#[test]
+	fn fuse_dna_keeps_rarer_gene() {
+		assert_eq!(fuse_dna(0b1111_1111, 0b0000_1111, 0), 0b1111_1111);
+		assert_eq!(fuse_dna(0b0101_0101, 0b0000_0001, 0), 0b0000_0001);
+		assert_eq!(fuse_dna(0b1111_0000, 0b0000_1111, 0b1111_1111), 0b1111_0000);
+		assert_eq!(fuse_dna(0b1111_0000, 0b0000_1111, 0), 0b0000_1111);
+	}
+
+	const TOURNAMENT: LockIdentifier = *b"tourname";
+
+	#[test]
+	fn locked_kitty_cannot_be_used() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(<Kitties as KittyLock<_>>::lock(0, TOURNAMENT));
+
+			assert!(<Kitties as KittyLock<_>>::is_locked(0));
+			assert_noop!(Kitties::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyLocked);
+			assert_noop!(Kitties::ask(Origin::signed(1), 0, Some(10)), Error::<Test>::KittyLocked);
+			assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::KittyLocked);
+			assert_noop!(Kitties::fuse(Origin::signed(1), 0, 1), Error::<Test>::KittyLocked);
+		});
+	}
+
+	#[test]
+	fn unlocked_kitty_can_be_transferred() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(<Kitties as KittyLock<_>>::lock(0, TOURNAMENT));
+			assert_noop!(<Kitties as KittyLock<_>>::lock(0, TOURNAMENT), Error::<Test>::AlreadyLocked);
+
+			assert_ok!(<Kitties as KittyLock<_>>::unlock(0, TOURNAMENT));
+			assert!(!<Kitties as KittyLock<_>>::is_locked(0));
+			assert_noop!(<Kitties as KittyLock<_>>::unlock(0, TOURNAMENT), Error::<Test>::NotLocked);
+
+			assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+			assert_eq!(Kitties::kitty_owner(0), Some(2));
+		});
+	}
+
+	#[test]
+	fn unique_assets_tracks_supply_and_owners() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&1, Kitty([0u8; 16])));
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&1, Kitty([1u8; 16])));
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&2, Kitty([2u8; 16])));
+
+			assert_eq!(<Kitties as UniqueAssets<_>>::total_supply(), 3);
+			assert_eq!(<Kitties as UniqueAssets<_>>::assets_of(&1), vec![0, 1]);
+			assert_eq!(<Kitties as UniqueAssets<_>>::owner_of(&2), Some(2));
+
+			assert_ok!(<Kitties as UniqueAssets<_>>::transfer(&2, &0));
+			assert_eq!(<Kitties as UniqueAssets<_>>::assets_of(&1), vec![1]);
+			assert_eq!(<Kitties as UniqueAssets<_>>::assets_of(&2), vec![2, 0]);
+
+			assert_ok!(<Kitties as UniqueAssets<_>>::burn(&1));
+			assert_eq!(<Kitties as UniqueAssets<_>>::total_supply(), 2);
+			assert_eq!(<Kitties as UniqueAssets<_>>::owner_of(&1), None);
+			assert!(<Kitties as UniqueAssets<_>>::assets_of(&1).is_empty());
+		});
+	}
+
+	#[test]
+	fn runtime_upgrade_counts_supply_of_existing_kitties() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&1, Kitty([0u8; 16])));
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&1, Kitty([1u8; 16])));
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&2, Kitty([2u8; 16])));
+			assert_ok!(<Kitties as UniqueAssets<_>>::burn(&1));
+			// As on a chain upgraded from before `KittiesSupply`
+			KittiesSupply::<Test>::kill();
+
+			<Kitties as frame_support::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+
+			assert_eq!(<Kitties as UniqueAssets<_>>::total_supply(), 2);
+		});
+	}
+
+	#[test]
+	fn transfer_delists_kitty() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 1, Some(10)));
+
+			assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+			assert_ok!(<Kitties as UniqueAssets<_>>::transfer(&2, &1));
+
+			assert_eq!(Kitties::kitty_price(0), None);
+			assert_eq!(Kitties::kitty_price(1), None);
+			assert_noop!(Kitties::buy(Origin::signed(1), 0, 10), Error::<Test>::NotForSale);
+			assert_noop!(Kitties::buy(Origin::signed(1), 1, 10), Error::<Test>::NotForSale);
+		});
+	}
+
+	#[test]
+	fn unique_assets_cannot_burn_locked_kitty() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&1, Kitty([0u8; 16])));
+			assert_ok!(<Kitties as KittyLock<_>>::lock(0, TOURNAMENT));
+
+			assert_noop!(<Kitties as UniqueAssets<_>>::burn(&0), Error::<Test>::KittyLocked);
+		});
+	}
+
+	#[test]
+	fn commit_reveal_creates_kitty() {
+		new_commit_reveal_test_ext().execute_with(|| {
+			let secret = H256::repeat_byte(7);
+
+			assert_noop!(Kitties::create(Origin::signed(1)), Error::<Test>::CommitRevealRequired);
+			assert_ok!(Kitties::commit(Origin::signed(1), BlakeTwo256::hash_of(&secret)));
+			assert_eq!(Balances::reserved_balance(1), 10);
+			assert_noop!(Kitties::reveal_create(Origin::signed(1), secret), Error::<Test>::RevealTooEarly);
+
+			System::set_block_number(2);
+			assert_noop!(Kitties::reveal_create(Origin::signed(1), H256::repeat_byte(8)), Error::<Test>::InvalidReveal);
+			assert_ok!(Kitties::reveal_create(Origin::signed(1), secret));
+
+			assert_eq!(Kitties::kitty_owner(0), Some(1));
+			assert_eq!(Kitties::commitment(1), None);
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::free_balance(1), 100);
+		});
+	}
+
+	#[test]
+	fn late_reveal_slashes_deposit() {
+		new_commit_reveal_test_ext().execute_with(|| {
+			let secret = H256::repeat_byte(7);
+
+			assert_ok!(Kitties::commit(Origin::signed(1), BlakeTwo256::hash_of(&secret)));
+
+			System::set_block_number(7);
+			assert_ok!(Kitties::reveal_create(Origin::signed(1), secret));
+
+			assert!(Kitties::kitties(0).is_none());
+			assert_eq!(Kitties::commitment(1), None);
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::free_balance(1), 90);
+		});
+	}
+
+	#[test]
+	fn commit_reveal_breeds_kitty() {
+		new_commit_reveal_test_ext().execute_with(|| {
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&1, Kitty([0u8; 16])));
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&1, Kitty([1u8; 16])));
+			let secret = H256::repeat_byte(7);
+
+			assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::CommitRevealRequired);
+			assert_ok!(Kitties::commit(Origin::signed(1), BlakeTwo256::hash_of(&secret)));
+			assert_noop!(Kitties::commit(Origin::signed(1), BlakeTwo256::hash_of(&secret)), Error::<Test>::AlreadyCommitted);
+
+			System::set_block_number(2);
+			assert_ok!(Kitties::reveal_breed(Origin::signed(1), secret, 0, 1));
+
+			assert_eq!(Kitties::kitty_owner(2), Some(1));
+		});
+	}
+
+	#[test]
+	fn commit_reveal_fuses_kitty() {
+		new_commit_reveal_test_ext().execute_with(|| {
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&1, Kitty([0u8; 16])));
+			assert_ok!(<Kitties as UniqueAssets<_>>::mint(&1, Kitty([1u8; 16])));
+			let secret = H256::repeat_byte(7);
+
+			assert_noop!(Kitties::fuse(Origin::signed(1), 0, 1), Error::<Test>::CommitRevealRequired);
+			assert_ok!(Kitties::commit(Origin::signed(1), BlakeTwo256::hash_of(&secret)));
+
+			System::set_block_number(2);
+			assert_noop!(Kitties::reveal_fuse(Origin::signed(2), secret, 0, 1), Error::<Test>::RequireOwner);
+			assert_ok!(Kitties::reveal_fuse(Origin::signed(1), secret, 0, 1));
+
+			assert!(Kitties::kitties(0).is_none());
+			assert!(Kitties::kitties(1).is_none());
+			assert_eq!(Kitties::kitty_owner(2), Some(1));
+			assert_eq!(Kitties::kitty_fusion_level(2), 1);
+			assert_eq!(Kitties::commitment(1), None);
+		});
+	}
+
+	#[test]
+	fn commit_requires_commit_reveal_mode() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Kitties::commit(Origin::signed(1), H256::repeat_byte(7)),
+				Error::<Test>::CommitRevealDisabled
+			);
+		});
+	}
+
+	#[test]
+	fn floor_price_tracks_listings() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..6 {
+				assert_ok!(Kitties::create(Origin::signed(1)));
+			}
+			assert_eq!(Kitties::floor_price(), None);
+
+			for (kitty_id, price) in [(0, 40), (1, 20), (2, 50), (3, 10), (4, 30), (5, 60)].iter() {
+				assert_ok!(Kitties::ask(Origin::signed(1), *kitty_id, Some(*price)));
+			}
+			assert_eq!(Kitties::floor_price(), Some(10));
+			assert_eq!(Kitties::listed_kitties_count(), 6);
+
+			// Delisting, selling, transferring and burning all take the kitty off the floor
+			assert_ok!(Kitties::ask(Origin::signed(1), 3, None));
+			assert_eq!(Kitties::floor_price(), Some(20));
+			assert_ok!(Kitties::buy(Origin::signed(2), 1, 20));
+			assert_eq!(Kitties::floor_price(), Some(30));
+			assert_ok!(Kitties::transfer(Origin::signed(1), 2, 4));
+			assert_eq!(Kitties::floor_price(), Some(40));
+			assert_ok!(<Kitties as UniqueAssets<_>>::burn(&0));
+			assert_eq!(Kitties::floor_price(), Some(50));
+
+			// Relisting moves the kitty both ways
+			assert_ok!(Kitties::ask(Origin::signed(1), 5, Some(5)));
+			assert_eq!(Kitties::floor_price(), Some(5));
+			assert_ok!(Kitties::ask(Origin::signed(1), 5, Some(70)));
+			assert_eq!(Kitties::floor_price(), Some(50));
+
+			assert_ok!(Kitties::ask(Origin::signed(1), 2, None));
+			assert_eq!(Kitties::floor_price(), Some(70));
+			assert_ok!(Kitties::ask(Origin::signed(1), 5, None));
+			assert_eq!(Kitties::floor_price(), None);
+			assert_eq!(Kitties::listed_kitties_count(), 0);
+		});
+	}
+
+	#[test]
+	fn buy_records_price_history_and_stats() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::create(Origin::signed(1)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+			assert_ok!(Kitties::ask(Origin::signed(1), 1, Some(5)));
+			assert_eq!(Kitties::floor_price(), Some(5));
+
+			assert_ok!(Kitties::buy(Origin::signed(2), 0, 10));
+			assert_eq!(Kitties::floor_price(), Some(5));
+			assert_ok!(Kitties::ask(Origin::signed(2), 0, Some(20)));
+			assert_ok!(Kitties::buy(Origin::signed(1), 0, 20));
+			assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(30)));
+			assert_ok!(Kitties::buy(Origin::signed(2), 0, 30));
+
+			let history = Kitties::price_history(0);
+			assert_eq!(history.len(), 2);
+			assert_eq!(history[0], SaleRecord { block: 0, price: 20, buyer: 1, seller: 2 });
+			assert_eq!(history[1], SaleRecord { block: 0, price: 30, buyer: 2, seller: 1 });
+
+			let stats = Kitties::market_stats();
+			assert_eq!(stats.volume, 60);
+			assert_eq!(stats.trade_count, 3);
+			assert_eq!(stats.last_sale, Some((0, SaleRecord { block: 0, price: 30, buyer: 2, seller: 1 })));
+		});
+	}
+}
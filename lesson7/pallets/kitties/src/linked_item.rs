@@ -1,6 +1,7 @@
 use frame_support::{StorageMap, Parameter};
 use sp_runtime::traits::Member;
 use codec::{Encode, Decode};
+use sp_std::prelude::*;
 
 #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
 #[derive(Encode, Decode)]
@@ -35,11 +36,57 @@ impl<Storage, Key, Value> LinkedList<Storage, Key, Value> where
 		Storage::insert((&key, value), item);
 	}
 
+	/// Collect all values in insertion order.
+	pub fn enumerate(key: &Key) -> Vec<Value> {
+		let mut values = Vec::new();
+		let mut next = Self::read_head(key).next;
+		while let Some(value) = next {
+			values.push(value);
+			next = Self::read(key, Some(value)).next;
+		}
+		values
+	}
+
 	pub fn append(key: &Key, value: Value) {
-        // 作业
+		let head = Self::read_head(key);
+		let new_head = LinkedItem {
+			prev: Some(value),
+			next: head.next,
+		};
+
+		Self::write_head(key, new_head);
+
+		let prev = Self::read(key, head.prev);
+		let new_prev = LinkedItem {
+			prev: prev.prev,
+			next: Some(value),
+		};
+		Self::write(key, head.prev, new_prev);
+
+		let item = LinkedItem {
+			prev: head.prev,
+			next: None,
+		};
+		Self::write(key, Some(value), item);
 	}
 
 	pub fn remove(key: &Key, value: Value) {
-        // 作业
+		if let Some(item) = Storage::take((&key, Some(value))) {
+			let prev = Self::read(key, item.prev);
+			let new_prev = LinkedItem {
+				prev: prev.prev,
+				next: item.next,
+			};
+
+			Self::write(key, item.prev, new_prev);
+
+			let next = Self::read(key, item.next);
+			let new_next = LinkedItem {
+				prev: item.prev,
+				next: next.next,
+			};
+
+			Self::write(key, item.next, new_next);
+		}
 	}
  }
\ No newline at end of file
@@ -0,0 +1,102 @@
+use frame_support::{StorageMap, StorageValue, Parameter};
+use sp_runtime::traits::Member;
+
+/// A binary min-heap of `(price, value)` entries kept in storage, so that the lowest price
+/// is read in O(1) and a value is listed, relisted or delisted in O(log n) storage accesses.
+///
+/// `Entries` maps heap positions to entries, `Positions` maps each value to its heap position
+/// and `Size` is the number of entries.
+pub struct PriceHeap<Entries, Positions, Size, Price, Value>(sp_std::marker::PhantomData<(Entries, Positions, Size, Price, Value)>);
+
+impl<Entries, Positions, Size, Price, Value> PriceHeap<Entries, Positions, Size, Price, Value> where
+	Price: Parameter + Member + Ord + Copy,
+	Value: Parameter + Member + Copy,
+	Entries: StorageMap<u32, (Price, Value), Query = Option<(Price, Value)>>,
+	Positions: StorageMap<Value, u32, Query = Option<u32>>,
+	Size: StorageValue<u32, Query = u32>,
+{
+	/// The entry with the lowest price.
+	pub fn peek() -> Option<(Price, Value)> {
+		Entries::get(0)
+	}
+
+	/// List `value` at `price`, or change its price if it is listed already.
+	pub fn insert(value: Value, price: Price) {
+		match Positions::get(value) {
+			Some(position) => {
+				Entries::insert(position, (price, value));
+				Self::sift_up(position);
+				Self::sift_down(position);
+			}
+			None => {
+				let position = Size::get();
+				Entries::insert(position, (price, value));
+				Positions::insert(value, position);
+				Size::put(position + 1);
+				Self::sift_up(position);
+			}
+		}
+	}
+
+	/// Delist `value`, if it is listed.
+	pub fn remove(value: Value) {
+		let position = match Positions::take(value) {
+			Some(position) => position,
+			None => return,
+		};
+		let last = Size::get() - 1;
+		let last_entry = Entries::take(last);
+		Size::put(last);
+
+		if position != last {
+			// Fill the hole with the last entry and restore the heap order from there
+			if let Some(entry) = last_entry {
+				Entries::insert(position, entry);
+				Positions::insert(entry.1, position);
+				Self::sift_up(position);
+				Self::sift_down(position);
+			}
+		}
+	}
+
+	fn price(position: u32) -> Option<Price> {
+		Entries::get(position).map(|(price, _)| price)
+	}
+
+	fn swap(a: u32, b: u32) {
+		if let (Some(entry_a), Some(entry_b)) = (Entries::get(a), Entries::get(b)) {
+			Entries::insert(a, entry_b);
+			Positions::insert(entry_b.1, a);
+			Entries::insert(b, entry_a);
+			Positions::insert(entry_a.1, b);
+		}
+	}
+
+	fn sift_up(mut position: u32) {
+		while position > 0 {
+			let parent = (position - 1) / 2;
+			if Self::price(position) >= Self::price(parent) {
+				break;
+			}
+			Self::swap(position, parent);
+			position = parent;
+		}
+	}
+
+	fn sift_down(mut position: u32) {
+		let size = Size::get();
+		loop {
+			let mut lowest = position;
+			for child in &[2 * position + 1, 2 * position + 2] {
+				if *child < size && Self::price(*child) < Self::price(lowest) {
+					lowest = *child;
+				}
+			}
+			if lowest == position {
+				break;
+			}
+			Self::swap(position, lowest);
+			position = lowest;
+		}
+	}
+}
@@ -0,0 +1,26 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_runtime::{DispatchError, DispatchResult};
+use sp_std::prelude::*;
+
+/// An interface over a set of non-fungible assets, so that other pallets
+/// can own, move and create assets without depending on the pallet storing them.
+pub trait UniqueAssets<AccountId> {
+	/// The type used to identify an asset.
+	type AssetId;
+	/// The data that makes an asset unique.
+	type AssetInfo;
+
+	/// The number of assets currently in existence.
+	fn total_supply() -> u64;
+	/// The owner of an asset, None if it does not exist.
+	fn owner_of(asset_id: &Self::AssetId) -> Option<AccountId>;
+	/// All assets owned by an account.
+	fn assets_of(owner: &AccountId) -> Vec<Self::AssetId>;
+	/// Create a new asset for the given owner.
+	fn mint(owner: &AccountId, info: Self::AssetInfo) -> Result<Self::AssetId, DispatchError>;
+	/// Destroy an asset.
+	fn burn(asset_id: &Self::AssetId) -> DispatchResult;
+	/// Move an asset from its current owner to a new one.
+	fn transfer(dest: &AccountId, asset_id: &Self::AssetId) -> DispatchResult;
+}
@@ -257,11 +257,22 @@ impl template::Trait for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const UseCommitReveal: bool = false;
+	pub const CommitDeposit: Balance = 1_000;
+	pub const RevealPeriod: BlockNumber = 10;
+	pub const MaxPriceHistory: u32 = 20;
+}
+
 impl pallet_kitties::Trait for Runtime {
 	type Event = Event;
 	type KittyIndex = u32;
 	type Currency = Balances;
 	type Randomness = RandomnessCollectiveFlip;
+	type UseCommitReveal = UseCommitReveal;
+	type CommitDeposit = CommitDeposit;
+	type RevealPeriod = RevealPeriod;
+	type MaxPriceHistory = MaxPriceHistory;
 }
 
 construct_runtime!(
@@ -395,6 +406,20 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_runtime_api::KittiesApi<Block, u32, AccountId, Balance, BlockNumber> for Runtime {
+		fn price_history(kitty_id: u32) -> Vec<pallet_kitties::SaleRecord<AccountId, Balance, BlockNumber>> {
+			Kitties::price_history(kitty_id)
+		}
+
+		fn market_stats() -> pallet_kitties::MarketStats<u32, AccountId, Balance, BlockNumber> {
+			Kitties::market_stats()
+		}
+
+		fn floor_price() -> Option<Balance> {
+			Kitties::floor_price()
+		}
+	}
+
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
 		fn grandpa_authorities() -> GrandpaAuthorityList {
 			Grandpa::grandpa_authorities()
@@ -9,8 +9,14 @@
 /// For more guidance on Substrate FRAME, see the example pallet
 /// https://github.com/paritytech/substrate/blob/master/frame/example/src/lib.rs
 
-use frame_support::{debug, decl_module, decl_storage, decl_event, decl_error, dispatch};
-use frame_system::{self as system, ensure_signed};
+use frame_support::{debug, decl_module, decl_storage, decl_event, decl_error, dispatch, ensure};
+use frame_system::{
+	self as system, ensure_signed, ensure_root,
+	offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+};
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{offchain::storage::StorageValueRef, traits::UniqueSaturatedInto};
+use sp_std::{convert::TryFrom, prelude::*};
 
 #[cfg(test)]
 mod mock;
@@ -18,12 +24,49 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+/// The key type of the accounts the offchain worker signs with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"sums");
+
+/// Offchain local storage key of the running sum, `(index, sum)`.
+const SUM_STORAGE_KEY: &[u8] = b"template::sum-of-squares";
+
+/// The app-specific crypto the offchain worker signs with.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		traits::Verify,
+	};
+	use sp_core::sr25519::Signature as Sr25519Signature;
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct AuthId;
+	impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature> for AuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+
+	// Runtimes using `MultiSignature` accounts
+	impl frame_system::offchain::AppCrypto<sp_runtime::MultiSigner, sp_runtime::MultiSignature> for AuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
 /// The pallet's configuration trait.
-pub trait Trait: system::Trait {
+pub trait Trait: system::Trait + CreateSignedTransaction<Call<Self>> {
 	// Add other types and constants required to configure this pallet.
 
+	/// The identifier type of the offchain worker.
+	type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The overarching dispatch call type.
+	type Call: From<Call<Self>>;
 }
 
 // This pallet's storage items.
@@ -32,20 +75,23 @@ decl_storage! {
 	// storage items are isolated from other pallets.
 	// ---------------------------------vvvvvvvvvvvvvv
 	trait Store for Module<T: Trait> as TemplateModule {
-		// Just a dummy storage item.
-		// Here we are declaring a StorageValue, `Something` as a Option<u32>
-		// `get(fn something)` is the default getter which returns either the stored `u32` or `None` if nothing stored
-		Something get(fn something): Option<u32>;
+		/// Sums of squares submitted by the offchain workers, `Numbers[n]` is `1^2 + ... + n^2`.
+		Numbers get(fn numbers): map hasher(twox_64_concat) u64 => Option<u64>;
+
+		/// Accounts allowed to submit sums of squares.
+		Authorities get(fn authorities) config(): Vec<T::AccountId>;
 	}
 }
 
 // The pallet's events
 decl_event!(
 	pub enum Event<T> where AccountId = <T as system::Trait>::AccountId {
-		/// Just a dummy event.
-		/// Event `Something` is declared with a parameter of the type `u32` and `AccountId`
-		/// To emit this event, we call the deposit function, from our runtime functions
-		SomethingStored(u32, AccountId),
+		/// A sum of squares is stored. (n, number, submitter)
+		NumberSaved(u64, u64, AccountId),
+		/// An account is allowed to submit sums of squares. (account)
+		AuthorityAdded(AccountId),
+		/// An account is no longer allowed to submit sums of squares. (account)
+		AuthorityRemoved(AccountId),
 	}
 );
 
@@ -56,6 +102,14 @@ decl_error! {
 		NoneValue,
 		/// Value reached maximum and cannot be incremented further
 		StorageOverflow,
+		/// The account is not allowed to submit sums of squares
+		NotAuthority,
+		/// The account is already allowed to submit sums of squares
+		AlreadyAuthority,
+		/// The sum of squares up to this number is already stored
+		NumberAlreadySaved,
+		/// The number is not the sum of squares it is submitted for
+		InvalidNumber,
 	}
 }
 
@@ -72,14 +126,51 @@ decl_module! {
 		// this is needed only if you are using events in your pallet
 		fn deposit_event() = default;
 
+		/// Store `1^2 + ... + n^2`. Only the first submission for each `n` is kept.
 		#[weight = 10_000]
-		pub fn save_number(origin, number: u32) -> dispatch::DispatchResult {
+		pub fn save_number(origin, n: u64, number: u64) -> dispatch::DispatchResult {
 			// Check it was signed and get the signer. See also: ensure_root and ensure_none
 			let who = ensure_signed(origin)?;
 
-			/*******
-			 * 学员们在这里追加逻辑
-			 *******/
+			ensure!(Self::authorities().contains(&who), Error::<T>::NotAuthority);
+
+			ensure!(!Numbers::contains_key(n), Error::<T>::NumberAlreadySaved);
+
+			ensure!(closed_sum_of_squares(n) == Some(number), Error::<T>::InvalidNumber);
+
+			Numbers::insert(n, number);
+
+			Self::deposit_event(RawEvent::NumberSaved(n, number, who));
+
+			Ok(())
+		}
+
+		/// Allow an account to submit sums of squares.
+		#[weight = 10_000]
+		pub fn add_authority(origin, who: T::AccountId) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			let mut authorities = Self::authorities();
+			ensure!(!authorities.contains(&who), Error::<T>::AlreadyAuthority);
+			authorities.push(who.clone());
+			Authorities::<T>::put(authorities);
+
+			Self::deposit_event(RawEvent::AuthorityAdded(who));
+
+			Ok(())
+		}
+
+		/// Stop an account from submitting sums of squares.
+		#[weight = 10_000]
+		pub fn remove_authority(origin, who: T::AccountId) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			let mut authorities = Self::authorities();
+			ensure!(authorities.contains(&who), Error::<T>::NotAuthority);
+			authorities.retain(|a| a != &who);
+			Authorities::<T>::put(authorities);
+
+			Self::deposit_event(RawEvent::AuthorityRemoved(who));
 
 			Ok(())
 		}
@@ -87,10 +178,84 @@ decl_module! {
 		fn offchain_worker(block_number: T::BlockNumber) {
 			debug::info!("Entering off-chain workers");
 
-			/*******
-			 * 学员们在这里追加逻辑
-			 *******/
+			let index: u64 = block_number.unique_saturated_into();
+			if let Err(e) = Self::submit_sum_of_squares(index) {
+				debug::error!("Error: {}", e);
+			}
+		}
+
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Compute `1^2 + ... + n^2` for block `n` and submit it in a signed transaction from one
+	/// of the local accounts.
+	fn submit_sum_of_squares(n: u64) -> Result<(), &'static str> {
+		let signer = Signer::<T, T::AuthorityId>::any_account();
+		if !signer.can_sign() {
+			return Err("No local accounts available. Consider adding one via `author_insertKey` RPC.");
+		}
+
+		let sum = match Self::next_sum_of_squares(n)? {
+			Some(sum) => sum,
+			// Already submitted for this block, e.g. when it is imported again on another fork
+			None => return Ok(()),
+		};
+
+		match signer.send_signed_transaction(|_account| Call::save_number(n, sum)) {
+			Some((account, Ok(()))) => debug::info!("[{:?}] Submitted sum of squares {}", account.id, sum),
+			Some((account, Err(e))) => {
+				debug::error!("[{:?}] Failed to submit transaction: {:?}", account.id, e);
+				return Err("Failed to submit the sum of squares");
+			}
+			None => return Err("No local account signed the sum of squares"),
 		}
 
+		// Only a submitted sum moves the running sum forward, a failed block is retried from the last one
+		Self::store_sum_of_squares(n, sum)
 	}
+
+	/// The sum of squares up to `n`, computed from the running sum kept in offchain local storage.
+	/// Returns None if the running sum is already at `n` or beyond.
+	fn next_sum_of_squares(n: u64) -> Result<Option<u64>, &'static str> {
+		let storage = StorageValueRef::persistent(SUM_STORAGE_KEY);
+
+		let sum = match storage.get::<(u64, u64)>() {
+			Some(Some((index, _))) if index >= n => return Ok(None),
+			Some(Some((index, sum))) => sum_of_squares(index, sum, n),
+			_ => sum_of_squares(0, 0, n),
+		};
+		sum.map(Some).ok_or("Sum of squares overflowed")
+	}
+
+	/// Advance the running sum kept in offchain local storage to `(n, sum)`, unless another
+	/// worker has already moved it to `n` or beyond.
+	fn store_sum_of_squares(n: u64, sum: u64) -> Result<(), &'static str> {
+		let storage = StorageValueRef::persistent(SUM_STORAGE_KEY);
+
+		let result = storage.mutate(|stored: Option<Option<(u64, u64)>>| {
+			match stored {
+				Some(Some((index, _))) if index >= n => Err(()),
+				_ => Ok((n, sum)),
+			}
+		});
+
+		match result {
+			Ok(Ok(_)) | Err(()) => Ok(()),
+			// Another worker updated the sum concurrently
+			Ok(Err(_)) => Err("Sum of squares is being updated by another worker"),
+		}
+	}
+}
+
+/// `1^2 + ... + n^2` as `n(n + 1)(2n + 1) / 6`, None if it does not fit in a u64.
+pub fn closed_sum_of_squares(n: u64) -> Option<u64> {
+	let n = n as u128;
+	let sum = n.checked_mul(n + 1)?.checked_mul(2 * n + 1)? / 6;
+	u64::try_from(sum).ok()
+}
+
+/// Add `(from + 1)^2 + ... + to^2` to `sum`, the sum of squares up to `from`.
+pub fn sum_of_squares(from: u64, sum: u64, to: u64) -> Option<u64> {
+	(from + 1..=to).try_fold(sum, |sum, i| i.checked_mul(i).and_then(|square| sum.checked_add(square)))
 }
@@ -1,18 +1,115 @@
 // Tests to be written here
 
-use crate::{Error, mock::*};
+use crate::{Call, Error, KEY_TYPE, closed_sum_of_squares, sum_of_squares, mock::*};
+use codec::Decode;
 use frame_support::{assert_ok, assert_noop};
+use sp_core::{
+	offchain::{OffchainExt, TransactionPoolExt, testing},
+	sr25519, testing::KeyStore, traits::KeystoreExt,
+};
 
 #[test]
 fn test_onchain() {
 	new_test_ext().execute_with(|| {
-		// Test onchain logic here
+		let who = sr25519::Public::from_raw([1; 32]);
+		assert_ok!(TemplateModule::add_authority(Origin::ROOT, who));
+
+		assert_ok!(TemplateModule::save_number(Origin::signed(who), 1, 1));
+		assert_ok!(TemplateModule::save_number(Origin::signed(who), 3, 14));
+		assert_noop!(TemplateModule::save_number(Origin::signed(who), 3, 14), Error::<Test>::NumberAlreadySaved);
+		assert_noop!(TemplateModule::save_number(Origin::signed(who), 2, 6), Error::<Test>::InvalidNumber);
+
+		assert_eq!(TemplateModule::numbers(1), Some(1));
+		assert_eq!(TemplateModule::numbers(2), None);
+		assert_eq!(TemplateModule::numbers(3), Some(14));
 	});
 }
 
 #[test]
-fn test_offchain() {
+fn test_authorities() {
 	new_test_ext().execute_with(|| {
-		// Test offchain worker here
+		let who = sr25519::Public::from_raw([1; 32]);
+
+		assert_noop!(TemplateModule::save_number(Origin::signed(who), 1, 1), Error::<Test>::NotAuthority);
+		assert_noop!(
+			TemplateModule::add_authority(Origin::signed(who), who),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(TemplateModule::add_authority(Origin::ROOT, who));
+		assert_noop!(TemplateModule::add_authority(Origin::ROOT, who), Error::<Test>::AlreadyAuthority);
+		assert_ok!(TemplateModule::save_number(Origin::signed(who), 1, 1));
+
+		assert_ok!(TemplateModule::remove_authority(Origin::ROOT, who));
+		assert_noop!(TemplateModule::save_number(Origin::signed(who), 2, 5), Error::<Test>::NotAuthority);
+		assert_eq!(TemplateModule::numbers(2), None);
+	});
+}
+
+#[test]
+fn test_sum_of_squares() {
+	assert_eq!(sum_of_squares(0, 0, 1), Some(1));
+	assert_eq!(sum_of_squares(1, 1, 3), Some(14));
+	assert_eq!(sum_of_squares(0, 0, 3), Some(14));
+	assert_eq!(sum_of_squares(3, 14, 3), Some(14));
+	assert_eq!(sum_of_squares(0, u64::max_value(), 1), None);
+
+	assert_eq!(closed_sum_of_squares(0), Some(0));
+	assert_eq!(closed_sum_of_squares(3), sum_of_squares(0, 0, 3));
+	assert_eq!(closed_sum_of_squares(1_000), sum_of_squares(0, 0, 1_000));
+	assert_eq!(closed_sum_of_squares(u64::max_value()), None);
+}
+
+#[test]
+fn test_offchain() {
+	let (offchain, _state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	keystore.write().sr25519_generate_new(KEY_TYPE, Some("//Alice")).unwrap();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(keystore));
+
+	t.execute_with(|| {
+		for block_number in 1..=3 {
+			TemplateModule::submit_sum_of_squares(block_number).unwrap();
+		}
+		// The sum for block 3 is only submitted once
+		TemplateModule::submit_sum_of_squares(3).unwrap();
+
+		let calls: Vec<_> = pool_state.write().transactions.drain(..)
+			.map(|tx| Extrinsic::decode(&mut &*tx).unwrap())
+			.map(|tx| {
+				assert!(tx.signature.is_some());
+				tx.call
+			})
+			.collect();
+		assert_eq!(calls, vec![Call::save_number(1, 1), Call::save_number(2, 5), Call::save_number(3, 14)]);
+	});
+}
+
+#[test]
+fn test_offchain_keeps_sum_until_submitted() {
+	let (offchain, _state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(keystore.clone()));
+
+	t.execute_with(|| {
+		assert!(TemplateModule::submit_sum_of_squares(1).is_err());
+
+		keystore.write().sr25519_generate_new(KEY_TYPE, Some("//Alice")).unwrap();
+		TemplateModule::submit_sum_of_squares(2).unwrap();
+
+		let calls: Vec<_> = pool_state.write().transactions.drain(..)
+			.map(|tx| Extrinsic::decode(&mut &*tx).unwrap().call)
+			.collect();
+		assert_eq!(calls, vec![Call::save_number(2, 5)]);
 	});
 }
@@ -1,7 +1,7 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	SudoConfig, SystemConfig, TemplateModuleConfig, WASM_BINARY, Signature
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -54,6 +54,9 @@ pub fn development_config() -> ChainSpec {
 				get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 			],
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+			],
 			true,
 		),
 		vec![],
@@ -89,6 +92,10 @@ pub fn local_testnet_config() -> ChainSpec {
 				get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 			],
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				get_account_id_from_seed::<sr25519::Public>("Bob"),
+			],
 			true,
 		),
 		vec![],
@@ -102,6 +109,7 @@ pub fn local_testnet_config() -> ChainSpec {
 fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	oracle_authorities: Vec<AccountId>,
 	_enable_println: bool) -> GenesisConfig {
 	GenesisConfig {
 		system: Some(SystemConfig {
@@ -120,5 +128,12 @@ fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
 		sudo: Some(SudoConfig {
 			key: root_key,
 		}),
+		template: Some(TemplateModuleConfig {
+			oracle_authorities,
+			providers: vec![
+				(b"https://api.coincap.io/v2/assets/ethereum".to_vec(), b"/data/priceUsd".to_vec(), 1),
+				(b"https://min-api.cryptocompare.com/data/price?fsym=ETH&tsyms=USD".to_vec(), b"/USD".to_vec(), 1),
+			],
+		}),
 	}
 }
@@ -90,6 +90,7 @@ pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceEr
 	let force_authoring = config.force_authoring;
 	let name = config.network.node_name.clone();
 	let disable_grandpa = config.disable_grandpa;
+	let dev_seed = config.dev_key_seed.clone();
 
 	let (builder, mut import_setup, inherent_data_providers) = new_full_start!(config);
 
@@ -105,6 +106,16 @@ pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceEr
 		})?
 		.build()?;
 
+	// Give the offchain worker of development chains an oracle key to sign prices with
+	if let Some(seed) = dev_seed {
+		service.keystore().write()
+			.insert_ephemeral_from_seed_by_type::<node_template_runtime::template::crypto::Pair>(
+				&seed,
+				node_template_runtime::template::KEY_TYPE,
+			)
+			.expect("Dev Seed should always succeed.");
+	}
+
 	if role.is_authority() {
 		let proposer = sc_basic_authorship::ProposerFactory::new(
 			service.client(),
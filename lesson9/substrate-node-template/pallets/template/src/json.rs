@@ -0,0 +1,349 @@
+//! A minimal JSON parser for the offchain worker, which cannot use `serde_json` in no_std.
+//!
+//! Numbers are kept as text and converted to fixed point on demand, so no floating point
+//! arithmetic is involved.
+
+use sp_std::prelude::*;
+
+/// Nesting deeper than this is rejected, to bound the recursion of the parser.
+const MAX_DEPTH: u32 = 32;
+
+/// A parsed JSON value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JsonValue {
+	Null,
+	Bool(bool),
+	/// A number, as it appears in the document.
+	Number(Vec<u8>),
+	/// A string with its escapes decoded, UTF-8 encoded.
+	String(Vec<u8>),
+	Array(Vec<JsonValue>),
+	/// The members of an object, in document order.
+	Object(Vec<(Vec<u8>, JsonValue)>),
+}
+
+impl JsonValue {
+	/// The value of the first member of an object named `key`.
+	pub fn get(&self, key: &[u8]) -> Option<&JsonValue> {
+		match self {
+			JsonValue::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+			_ => None,
+		}
+	}
+
+	/// Look up a value by an RFC 6901 JSON pointer such as `/data/priceUsd`.
+	pub fn pointer(&self, pointer: &[u8]) -> Option<&JsonValue> {
+		if pointer.is_empty() {
+			return Some(self);
+		}
+		if pointer[0] != b'/' {
+			return None;
+		}
+
+		pointer[1..].split(|b| *b == b'/').try_fold(self, |value, token| {
+			let token = unescape_token(token)?;
+			match value {
+				JsonValue::Object(_) => value.get(&token),
+				JsonValue::Array(items) => parse_index(&token).and_then(|i| items.get(i)),
+				_ => None,
+			}
+		})
+	}
+
+	/// A number, or a string holding one, as a fixed point integer with `decimals` decimals.
+	/// Extra decimals are truncated. Negative and out of range numbers give None.
+	pub fn as_fixed_point(&self, decimals: u32) -> Option<u64> {
+		match self {
+			JsonValue::Number(text) | JsonValue::String(text) => parse_fixed_point(text, decimals),
+			_ => None,
+		}
+	}
+}
+
+/// Parse a complete JSON document.
+pub fn parse(input: &[u8]) -> Option<JsonValue> {
+	let mut parser = Parser { input, pos: 0, depth: 0 };
+	let value = parser.value()?;
+	parser.skip_whitespace();
+	if parser.pos == input.len() {
+		Some(value)
+	} else {
+		None
+	}
+}
+
+/// Parse a non-negative decimal number, e.g. `2345.67` or `2.34567e3`, as a fixed point integer.
+pub fn parse_fixed_point(text: &[u8], decimals: u32) -> Option<u64> {
+	let (mantissa, exponent) = match text.iter().position(|b| *b == b'e' || *b == b'E') {
+		Some(i) => (&text[..i], parse_exponent(&text[i + 1..])?),
+		None => (text, 0),
+	};
+	let (int, frac) = match mantissa.iter().position(|b| *b == b'.') {
+		Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+		None => (mantissa, &[][..]),
+	};
+	if int.is_empty() || (frac.is_empty() && int.len() < mantissa.len()) {
+		return None;
+	}
+	if !int.iter().chain(frac.iter()).all(u8::is_ascii_digit) {
+		return None;
+	}
+
+	// The number is `digits * 10^(exponent - frac.len())`, keep the digits above 10^-decimals
+	let digits = int.iter().chain(frac.iter());
+	let kept = int.len() as i64 + exponent + decimals as i64;
+	if kept <= 0 {
+		return Some(0);
+	}
+	let kept = kept as usize;
+	let mut value = digits.clone().take(kept).try_fold(0u64, |acc, d| {
+		acc.checked_mul(10)?.checked_add((d - b'0') as u64)
+	})?;
+	for _ in digits.count()..kept {
+		value = value.checked_mul(10)?;
+	}
+	Some(value)
+}
+
+fn parse_exponent(text: &[u8]) -> Option<i64> {
+	let (negative, digits) = match text.first() {
+		Some(b'-') => (true, &text[1..]),
+		Some(b'+') => (false, &text[1..]),
+		_ => (false, text),
+	};
+	// Larger exponents over- or underflow any price anyway
+	if digits.is_empty() || digits.len() > 4 || !digits.iter().all(u8::is_ascii_digit) {
+		return None;
+	}
+	let exponent = digits.iter().fold(0i64, |acc, d| acc * 10 + (d - b'0') as i64);
+	Some(if negative { -exponent } else { exponent })
+}
+
+fn parse_index(token: &[u8]) -> Option<usize> {
+	if token.is_empty() || token.len() > 9 || !token.iter().all(u8::is_ascii_digit) {
+		return None;
+	}
+	Some(token.iter().fold(0usize, |acc, d| acc * 10 + (d - b'0') as usize))
+}
+
+/// Decode the `~1` and `~0` escapes of a JSON pointer token.
+fn unescape_token(token: &[u8]) -> Option<Vec<u8>> {
+	let mut out = Vec::with_capacity(token.len());
+	let mut i = 0;
+	while i < token.len() {
+		match token[i] {
+			b'~' => {
+				match token.get(i + 1)? {
+					b'0' => out.push(b'~'),
+					b'1' => out.push(b'/'),
+					_ => return None,
+				}
+				i += 2;
+			}
+			b => {
+				out.push(b);
+				i += 1;
+			}
+		}
+	}
+	Some(out)
+}
+
+struct Parser<'a> {
+	input: &'a [u8],
+	pos: usize,
+	depth: u32,
+}
+
+impl<'a> Parser<'a> {
+	fn peek(&self) -> Option<u8> {
+		self.input.get(self.pos).copied()
+	}
+
+	fn bump(&mut self) -> Option<u8> {
+		let b = self.peek()?;
+		self.pos += 1;
+		Some(b)
+	}
+
+	fn expect(&mut self, expected: u8) -> Option<()> {
+		if self.bump()? == expected {
+			Some(())
+		} else {
+			None
+		}
+	}
+
+	fn skip_whitespace(&mut self) {
+		while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+			self.pos += 1;
+		}
+	}
+
+	fn literal(&mut self, literal: &[u8], value: JsonValue) -> Option<JsonValue> {
+		if self.input[self.pos..].starts_with(literal) {
+			self.pos += literal.len();
+			Some(value)
+		} else {
+			None
+		}
+	}
+
+	fn value(&mut self) -> Option<JsonValue> {
+		self.skip_whitespace();
+		match self.peek()? {
+			b'n' => self.literal(b"null", JsonValue::Null),
+			b't' => self.literal(b"true", JsonValue::Bool(true)),
+			b'f' => self.literal(b"false", JsonValue::Bool(false)),
+			b'"' => self.string().map(JsonValue::String),
+			b'[' => self.nested(Self::array),
+			b'{' => self.nested(Self::object),
+			b'-' | b'0'..=b'9' => self.number(),
+			_ => None,
+		}
+	}
+
+	fn nested(&mut self, parse: fn(&mut Self) -> Option<JsonValue>) -> Option<JsonValue> {
+		if self.depth >= MAX_DEPTH {
+			return None;
+		}
+		self.depth += 1;
+		let value = parse(self);
+		self.depth -= 1;
+		value
+	}
+
+	fn array(&mut self) -> Option<JsonValue> {
+		self.expect(b'[')?;
+		let mut items = Vec::new();
+		self.skip_whitespace();
+		if self.peek()? == b']' {
+			self.pos += 1;
+			return Some(JsonValue::Array(items));
+		}
+		loop {
+			items.push(self.value()?);
+			self.skip_whitespace();
+			match self.bump()? {
+				b',' => continue,
+				b']' => return Some(JsonValue::Array(items)),
+				_ => return None,
+			}
+		}
+	}
+
+	fn object(&mut self) -> Option<JsonValue> {
+		self.expect(b'{')?;
+		let mut members = Vec::new();
+		self.skip_whitespace();
+		if self.peek()? == b'}' {
+			self.pos += 1;
+			return Some(JsonValue::Object(members));
+		}
+		loop {
+			self.skip_whitespace();
+			let key = self.string()?;
+			self.skip_whitespace();
+			self.expect(b':')?;
+			members.push((key, self.value()?));
+			self.skip_whitespace();
+			match self.bump()? {
+				b',' => continue,
+				b'}' => return Some(JsonValue::Object(members)),
+				_ => return None,
+			}
+		}
+	}
+
+	fn number(&mut self) -> Option<JsonValue> {
+		let start = self.pos;
+		if self.peek() == Some(b'-') {
+			self.pos += 1;
+		}
+		match self.bump()? {
+			b'0' => (),
+			b'1'..=b'9' => self.digits(),
+			_ => return None,
+		}
+		if self.peek() == Some(b'.') {
+			self.pos += 1;
+			self.expect_digits()?;
+		}
+		if let Some(b'e') | Some(b'E') = self.peek() {
+			self.pos += 1;
+			if let Some(b'+') | Some(b'-') = self.peek() {
+				self.pos += 1;
+			}
+			self.expect_digits()?;
+		}
+		Some(JsonValue::Number(self.input[start..self.pos].to_vec()))
+	}
+
+	fn digits(&mut self) {
+		while let Some(b'0'..=b'9') = self.peek() {
+			self.pos += 1;
+		}
+	}
+
+	fn expect_digits(&mut self) -> Option<()> {
+		match self.bump()? {
+			b'0'..=b'9' => {
+				self.digits();
+				Some(())
+			}
+			_ => None,
+		}
+	}
+
+	fn string(&mut self) -> Option<Vec<u8>> {
+		self.expect(b'"')?;
+		let mut out = Vec::new();
+		loop {
+			match self.bump()? {
+				b'"' => return Some(out),
+				b'\\' => match self.bump()? {
+					b'"' => out.push(b'"'),
+					b'\\' => out.push(b'\\'),
+					b'/' => out.push(b'/'),
+					b'b' => out.push(0x08),
+					b'f' => out.push(0x0c),
+					b'n' => out.push(b'\n'),
+					b'r' => out.push(b'\r'),
+					b't' => out.push(b'\t'),
+					b'u' => {
+						let c = self.unicode_escape()?;
+						let mut buf = [0u8; 4];
+						out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+					}
+					_ => return None,
+				},
+				0x00..=0x1f => return None,
+				b => out.push(b),
+			}
+		}
+	}
+
+	/// Decode the code point of a `\u` escape, combining surrogate pairs.
+	fn unicode_escape(&mut self) -> Option<char> {
+		let high = self.hex4()?;
+		let code = if (0xd800..0xdc00).contains(&high) {
+			self.expect(b'\\')?;
+			self.expect(b'u')?;
+			let low = self.hex4()?;
+			if !(0xdc00..0xe000).contains(&low) {
+				return None;
+			}
+			0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+		} else {
+			high
+		};
+		core::char::from_u32(code)
+	}
+
+	fn hex4(&mut self) -> Option<u32> {
+		(0..4).try_fold(0u32, |acc, _| {
+			let digit = (self.bump()? as char).to_digit(16)?;
+			Some(acc * 16 + digit)
+		})
+	}
+}
@@ -9,8 +9,27 @@
 /// For more guidance on Substrate FRAME, see the example pallet
 /// https://github.com/paritytech/substrate/blob/master/frame/example/src/lib.rs
 
-use frame_support::{debug, decl_module, decl_storage, decl_event, decl_error, dispatch};
-use frame_system::{self as system, ensure_signed};
+use codec::{Encode, Decode};
+use frame_support::{
+	debug, decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, IterableStorageMap,
+	traits::{Get, Time},
+};
+use frame_system::{
+	self as system, ensure_signed, ensure_none, ensure_root,
+	offchain::{AppCrypto, SendTransactionTypes, SendUnsignedTransaction, SignedPayload, Signer, SigningTypes},
+};
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{
+	Percent, Permill, RuntimeDebug,
+	offchain::{http, Duration, storage::StorageValueRef},
+	traits::{IdentifyAccount, Saturating, UniqueSaturatedInto},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+	},
+};
+use sp_std::prelude::*;
+
+pub mod json;
 
 #[cfg(test)]
 mod mock;
@@ -18,12 +37,154 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+/// An ETH price in US cents.
+pub type Price = u64;
+
+/// Number of decimals of a `Price` in US dollars.
+pub const PRICE_DECIMALS: u32 = 2;
+
+/// How long the offchain worker waits for the providers, in milliseconds.
+const FETCH_TIMEOUT_MS: u64 = 3_000;
+
+/// Responses longer than this, in bytes, are discarded without being read further.
+const MAX_RESPONSE_LENGTH: usize = 16 * 1024;
+
+/// Offchain local storage key of the averaged prices.
+const PRICES_STORAGE_KEY: &[u8] = b"template::eth-prices";
+
+/// The key type of the oracle authorities.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"orcl");
+
+/// The app-specific crypto oracle authorities sign price payloads with.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		traits::Verify,
+	};
+	use sp_core::sr25519::Signature as Sr25519Signature;
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct AuthId;
+	impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature> for AuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+
+	// Runtimes using `MultiSignature` accounts
+	impl frame_system::offchain::AppCrypto<sp_runtime::MultiSigner, sp_runtime::MultiSignature> for AuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// A price signed by an oracle authority, submitted in an unsigned transaction.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PricePayload<Public, BlockNumber> {
+	pub price: Price,
+	/// The block the price was fetched at.
+	pub block_number: BlockNumber,
+	/// The key of the authority that signed the payload.
+	pub public: Public,
+}
+
+impl<T: SigningTypes> SignedPayload<T> for PricePayload<T::Public, T::BlockNumber> {
+	fn public(&self) -> T::Public {
+		self.public.clone()
+	}
+}
+
+/// How the prices of several sources are combined into one.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum AggregationMethod {
+	/// The weighted mean.
+	Mean,
+	/// The weighted median, the lower one on a tie.
+	Median,
+	/// The weighted mean once this share of the weight is dropped at each end. Must be below 50%.
+	TrimmedMean(Percent),
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct AggregationConfig {
+	pub method: AggregationMethod,
+	/// Prices further than this from the median are rejected before aggregating. None keeps them all.
+	pub max_deviation: Option<Permill>,
+}
+
+impl Default for AggregationConfig {
+	fn default() -> Self {
+		AggregationConfig {
+			method: AggregationMethod::Median,
+			max_deviation: Some(Permill::from_percent(10)),
+		}
+	}
+}
+
+pub type MomentOf<T> = <<T as Trait>::Time as Time>::Moment;
+
+/// A price stored on chain, with the time it was submitted at.
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct TimestampedPrice<Moment> {
+	pub price: Price,
+	pub timestamp: Moment,
+}
+
+/// The ETH price, as exposed to other pallets.
+pub trait PriceFeed {
+	/// The current price in US cents, None while the feed is stale.
+	fn eth_price() -> Option<Price>;
+}
+
+/// Identifier of a price provider in the registry.
+pub type ProviderId = u32;
+
+/// An HTTP endpoint serving the ETH/USD price. It is queried without any header, so providers
+/// requiring an API key, such as coinmarketcap, are not supported.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Provider {
+	/// UTF-8 URL queried with a GET request.
+	pub url: Vec<u8>,
+	/// JSON pointer to the price in the response, e.g. `/data/priceUsd` for coincap.
+	pub price_pointer: Vec<u8>,
+	/// Weight of the provider's price in the aggregation.
+	pub weight: u32,
+	/// Disabled providers are kept in the registry but not queried.
+	pub enabled: bool,
+}
+
 /// The pallet's configuration trait.
-pub trait Trait: system::Trait {
+pub trait Trait: system::Trait + SendTransactionTypes<Call<Self>> + SigningTypes {
 	// Add other types and constants required to configure this pallet.
 
+	/// The identifier type of the oracle authorities.
+	type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The clock prices are timestamped with.
+	type Time: Time;
+
+	/// Prices older than this are not exposed anymore, and the feed is marked stale without any.
+	type MaxPriceAge: Get<MomentOf<Self>>;
+
+	/// The maximum number of providers in the registry.
+	type MaxProviders: Get<u32>;
+
+	/// The maximum length of a provider's URL and JSON pointer.
+	type MaxProviderFieldLength: Get<u32>;
+
+	/// The maximum number of prices kept, both in offchain and onchain storage. The oldest are dropped first.
+	type MaxPrices: Get<u32>;
+
+	/// The minimum number of blocks between two unsigned submissions of an authority.
+	type UnsignedInterval: Get<Self::BlockNumber>;
+
+	/// The priority of unsigned price submissions.
+	type UnsignedPriority: Get<TransactionPriority>;
 }
 
 // This pallet's storage items.
@@ -36,6 +197,46 @@ decl_storage! {
 		// Here we are declaring a StorageValue, `Something` as a Option<u32>
 		// `get(fn something)` is the default getter which returns either the stored `u32` or `None` if nothing stored
 		Something get(fn something): Option<u32>;
+
+		/// Prices submitted by the oracle authorities, oldest first, at most `MaxPrices` of them.
+		Prices get(fn prices): Vec<TimestampedPrice<MomentOf<T>>>;
+
+		/// Whether the feed has no price younger than `MaxPriceAge`.
+		IsStale get(fn is_stale): bool;
+
+		/// How the offchain worker combines the providers, and `current_price` the submitted prices.
+		Aggregation get(fn aggregation): AggregationConfig;
+
+		/// Accounts allowed to submit prices.
+		OracleAuthorities get(fn oracle_authorities) config(): Vec<T::AccountId>;
+
+		/// The block from which each authority may submit an unsigned price again.
+		NextUnsignedAt get(fn next_unsigned_at): map hasher(blake2_128_concat) T::AccountId => T::BlockNumber;
+
+		/// The providers the offchain worker fetches the ETH price from.
+		Providers get(fn providers): map hasher(twox_64_concat) ProviderId => Option<Provider>;
+
+		/// The id given to the next registered provider.
+		NextProviderId get(fn next_provider_id): ProviderId;
+
+		/// The number of providers in the registry.
+		ProviderCount get(fn provider_count): u32;
+	}
+	add_extra_genesis {
+		/// Enabled providers, as (url, price pointer, weight).
+		config(providers): Vec<(Vec<u8>, Vec<u8>, u32)>;
+		build(|config: &GenesisConfig<T>| {
+			for (url, price_pointer, weight) in &config.providers {
+				let provider = Provider {
+					url: url.clone(),
+					price_pointer: price_pointer.clone(),
+					weight: *weight,
+					enabled: true,
+				};
+				Module::<T>::ensure_valid_provider(&provider).expect("Genesis providers must be valid");
+				Module::<T>::insert_provider(provider).expect("Too many genesis providers");
+			}
+		});
 	}
 }
 
@@ -46,6 +247,24 @@ decl_event!(
 		/// Event `Something` is declared with a parameter of the type `u32` and `AccountId`
 		/// To emit this event, we call the deposit function, from our runtime functions
 		SomethingStored(u32, AccountId),
+		/// A price is submitted by an oracle authority. (price, authority)
+		NewPrice(Price, AccountId),
+		/// An account is allowed to submit prices.
+		OracleAuthorityAdded(AccountId),
+		/// An account is no longer allowed to submit prices.
+		OracleAuthorityRemoved(AccountId),
+		/// A price provider is registered.
+		ProviderAdded(ProviderId),
+		/// A price provider is changed.
+		ProviderUpdated(ProviderId),
+		/// A price provider is removed from the registry.
+		ProviderRemoved(ProviderId),
+		/// The way prices are aggregated is changed.
+		AggregationChanged(AggregationConfig),
+		/// No price is younger than `MaxPriceAge` anymore.
+		PriceFeedStale,
+		/// A fresh price is submitted to a stale feed.
+		PriceFeedRecovered,
 	}
 );
 
@@ -56,6 +275,22 @@ decl_error! {
 		NoneValue,
 		/// Value reached maximum and cannot be incremented further
 		StorageOverflow,
+		/// The sender is not an oracle authority
+		NotOracleAuthority,
+		/// The account is already an oracle authority
+		AlreadyOracleAuthority,
+		/// The provider registry is full
+		TooManyProviders,
+		/// The provider does not exist
+		ProviderNotExist,
+		/// The URL is not an http(s) UTF-8 URL or is too long
+		InvalidProviderUrl,
+		/// The price pointer is not a JSON pointer or is too long
+		InvalidPricePointer,
+		/// The weight of a provider must be positive
+		ZeroProviderWeight,
+		/// A trimmed mean must keep some prices
+		InvalidAggregation,
 	}
 }
 
@@ -84,13 +319,432 @@ decl_module! {
 			Ok(())
 		}
 
+		/// Submit a price in a signed transaction, paying fees.
+		#[weight = 10_000]
+		pub fn submit_price(origin, price: Price) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Self::oracle_authorities().contains(&who), Error::<T>::NotOracleAuthority);
+
+			Self::add_price(who, price);
+
+			Ok(())
+		}
+
+		/// Submit a price signed by an oracle authority in an unsigned transaction, free of fees.
+		/// The payload is checked in `validate_unsigned`.
+		#[weight = 10_000]
+		pub fn submit_price_unsigned_with_signed_payload(
+			origin,
+			payload: PricePayload<T::Public, T::BlockNumber>,
+			_signature: T::Signature,
+		) -> dispatch::DispatchResult {
+			ensure_none(origin)?;
+
+			let authority = payload.public.into_account();
+			let now = system::Module::<T>::block_number();
+			NextUnsignedAt::<T>::insert(&authority, now.saturating_add(T::UnsignedInterval::get()));
+
+			Self::add_price(authority, payload.price);
+
+			Ok(())
+		}
+
+		#[weight = 10_000]
+		pub fn add_oracle_authority(origin, who: T::AccountId) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			let mut authorities = Self::oracle_authorities();
+			ensure!(!authorities.contains(&who), Error::<T>::AlreadyOracleAuthority);
+			authorities.push(who.clone());
+			OracleAuthorities::<T>::put(authorities);
+
+			Self::deposit_event(RawEvent::OracleAuthorityAdded(who));
+
+			Ok(())
+		}
+
+		#[weight = 10_000]
+		pub fn remove_oracle_authority(origin, who: T::AccountId) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			let mut authorities = Self::oracle_authorities();
+			ensure!(authorities.contains(&who), Error::<T>::NotOracleAuthority);
+			authorities.retain(|a| a != &who);
+			OracleAuthorities::<T>::put(authorities);
+			// `NextUnsignedAt` is kept, so the old payloads of an authority added again stay stale
+
+			Self::deposit_event(RawEvent::OracleAuthorityRemoved(who));
+
+			Ok(())
+		}
+
+		#[weight = 10_000]
+		pub fn add_provider(origin, provider: Provider) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			Self::ensure_valid_provider(&provider)?;
+			let id = Self::insert_provider(provider)?;
+
+			Self::deposit_event(RawEvent::ProviderAdded(id));
+
+			Ok(())
+		}
+
+		/// Replace a provider's configuration, e.g. to disable it or change its weight.
+		#[weight = 10_000]
+		pub fn update_provider(origin, id: ProviderId, provider: Provider) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			ensure!(Providers::contains_key(id), Error::<T>::ProviderNotExist);
+			Self::ensure_valid_provider(&provider)?;
+			Providers::insert(id, provider);
+
+			Self::deposit_event(RawEvent::ProviderUpdated(id));
+
+			Ok(())
+		}
+
+		#[weight = 10_000]
+		pub fn remove_provider(origin, id: ProviderId) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			ensure!(Providers::contains_key(id), Error::<T>::ProviderNotExist);
+			Providers::remove(id);
+			ProviderCount::mutate(|count| *count = count.saturating_sub(1));
+
+			Self::deposit_event(RawEvent::ProviderRemoved(id));
+
+			Ok(())
+		}
+
+		#[weight = 10_000]
+		pub fn set_aggregation(origin, config: AggregationConfig) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			if let AggregationMethod::TrimmedMean(trim) = config.method {
+				ensure!(trim < Percent::from_percent(50), Error::<T>::InvalidAggregation);
+			}
+			Aggregation::put(config);
+
+			Self::deposit_event(RawEvent::AggregationChanged(config));
+
+			Ok(())
+		}
+
+		fn on_finalize(_n: T::BlockNumber) {
+			if !Self::is_stale() && Self::current_price().is_none() {
+				IsStale::put(true);
+				Self::deposit_event(RawEvent::PriceFeedStale);
+			}
+		}
+
 		fn offchain_worker(block_number: T::BlockNumber) {
 			debug::info!("Entering off-chain workers");
 
-			/*******
-			 * 学员们在这里追加逻辑
-			 *******/
+			match Self::fetch_price() {
+				Ok(price) => {
+					debug::info!("ETH price at block {:?}: {} cents", block_number, price);
+					Self::append_price(price);
+					if let Err(e) = Self::submit_price_unsigned(block_number, price) {
+						debug::error!("Error: {}", e);
+					}
+				}
+				Err(e) => debug::error!("Error: {}", e),
+			}
+		}
+
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The averaged prices kept in offchain storage, oldest first. Only available offchain.
+	pub fn offchain_prices() -> Vec<Price> {
+		StorageValueRef::persistent(PRICES_STORAGE_KEY).get().flatten().unwrap_or_default()
+	}
+
+	/// Append a price to `Prices`, dropping the oldest beyond `MaxPrices`.
+	fn add_price(who: T::AccountId, price: Price) {
+		let timestamp = T::Time::now();
+		Prices::<T>::mutate(|prices| {
+			prices.push(TimestampedPrice { price, timestamp });
+			let max = T::MaxPrices::get() as usize;
+			if prices.len() > max {
+				let excess = prices.len() - max;
+				prices.drain(..excess);
+			}
+		});
+
+		Self::deposit_event(RawEvent::NewPrice(price, who));
+
+		if Self::is_stale() {
+			IsStale::put(false);
+			Self::deposit_event(RawEvent::PriceFeedRecovered);
+		}
+	}
+
+	/// The aggregation of the prices younger than `MaxPriceAge`, None if there are none.
+	pub fn current_price() -> Option<Price> {
+		let now = T::Time::now();
+		let max_age = T::MaxPriceAge::get();
+		let prices = Self::prices().into_iter()
+			.filter(|p| p.timestamp.saturating_add(max_age) >= now)
+			.map(|p| (p.price, 1))
+			.collect::<Vec<_>>();
+		aggregate(&prices, &Self::aggregation())
+	}
+
+	/// Sign the price with every local authority key and submit it in unsigned transactions.
+	fn submit_price_unsigned(block_number: T::BlockNumber, price: Price) -> Result<(), &'static str> {
+		let results = Signer::<T, T::AuthorityId>::all_accounts()
+			.send_unsigned_transaction(
+				|account| PricePayload {
+					price,
+					block_number,
+					public: account.public.clone(),
+				},
+				|payload, signature| Call::submit_price_unsigned_with_signed_payload(payload, signature),
+			);
+		if results.is_empty() {
+			return Err("No local accounts available. Consider adding one via `author_insertKey` RPC.");
+		}
+
+		for (account, result) in &results {
+			match result {
+				Ok(()) => debug::info!("[{:?}] Submitted price of {} cents", account.id, price),
+				Err(e) => debug::error!("[{:?}] Failed to submit transaction: {:?}", account.id, e),
+			}
+		}
+
+		Ok(())
+	}
+
+	fn ensure_valid_provider(provider: &Provider) -> dispatch::DispatchResult {
+		let max_length = T::MaxProviderFieldLength::get() as usize;
+		let url_is_valid = provider.url.len() <= max_length
+			&& (provider.url.starts_with(b"http://") || provider.url.starts_with(b"https://"))
+			&& core::str::from_utf8(&provider.url).is_ok();
+		ensure!(url_is_valid, Error::<T>::InvalidProviderUrl);
+		let pointer_is_valid = provider.price_pointer.len() <= max_length
+			&& (provider.price_pointer.is_empty() || provider.price_pointer[0] == b'/');
+		ensure!(pointer_is_valid, Error::<T>::InvalidPricePointer);
+		ensure!(provider.weight > 0, Error::<T>::ZeroProviderWeight);
+
+		Ok(())
+	}
+
+	/// Register a validated provider under the next id.
+	fn insert_provider(provider: Provider) -> Result<ProviderId, dispatch::DispatchError> {
+		ensure!(Self::provider_count() < T::MaxProviders::get(), Error::<T>::TooManyProviders);
+		let id = Self::next_provider_id();
+		let next_id = id.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
+
+		Providers::insert(id, provider);
+		NextProviderId::put(next_id);
+		ProviderCount::mutate(|count| *count += 1);
+
+		Ok(id)
+	}
+
+	/// The enabled providers, in registration order.
+	pub fn enabled_providers() -> Vec<Provider> {
+		let mut providers = Providers::iter()
+			.filter(|(_, provider)| provider.enabled)
+			.collect::<Vec<_>>();
+		providers.sort_by_key(|(id, _)| *id);
+		providers.into_iter().map(|(_, provider)| provider).collect()
+	}
+
+	/// Fetch the price from every enabled provider and aggregate the ones that answered.
+	fn fetch_price() -> Result<Price, &'static str> {
+		let providers = Self::enabled_providers();
+		if providers.is_empty() {
+			return Err("No provider is enabled");
 		}
+		aggregate(&Self::fetch_prices(&providers), &Self::aggregation()).ok_or("No provider returned a price")
+	}
+
+	/// Query all providers at once and return the prices that could be parsed, with their weights.
+	fn fetch_prices(providers: &[Provider]) -> Vec<(Price, u32)> {
+		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MS));
+
+		let (providers, requests): (Vec<_>, Vec<_>) = providers.iter()
+			.filter_map(|provider| {
+				// Checked when the provider is registered
+				let url = core::str::from_utf8(&provider.url).ok()?;
+				http::Request::get(url)
+					.deadline(deadline)
+					.send()
+					.map_err(|e| debug::warn!("Failed to query {}: {:?}", url, e))
+					.ok()
+					.map(|request| ((url, provider), request))
+			})
+			.unzip();
+
+		http::PendingRequest::try_wait_all(requests, deadline)
+			.into_iter()
+			.zip(providers)
+			.filter_map(|(result, (url, provider))| {
+				let response = match result {
+					Ok(Ok(response)) => response,
+					Ok(Err(e)) => {
+						debug::warn!("Failed to query {}: {:?}", url, e);
+						return None;
+					}
+					Err(_) => {
+						debug::warn!("{} did not answer in time", url);
+						return None;
+					}
+				};
+				if response.code != 200 {
+					debug::warn!("Unexpected status code from {}: {}", url, response.code);
+					return None;
+				}
+
+				let body = response.body().take(MAX_RESPONSE_LENGTH + 1).collect::<Vec<u8>>();
+				if body.len() > MAX_RESPONSE_LENGTH {
+					debug::warn!("Response from {} is longer than {} bytes", url, MAX_RESPONSE_LENGTH);
+					return None;
+				}
+				let price = parse_price(&body, &provider.price_pointer);
+				if price.is_none() {
+					debug::warn!("Unable to extract a price from {}", url);
+				}
+				price.map(|price| (price, provider.weight))
+			})
+			.collect()
+	}
+
+	/// Append a price to offchain storage, dropping the oldest beyond `MaxPrices`.
+	fn append_price(price: Price) {
+		let storage = StorageValueRef::persistent(PRICES_STORAGE_KEY);
+		let result = storage.mutate(|prices: Option<Option<Vec<Price>>>| -> Result<_, ()> {
+			let mut prices = prices.flatten().unwrap_or_default();
+			prices.push(price);
+			let max = T::MaxPrices::get() as usize;
+			if prices.len() > max {
+				let excess = prices.len() - max;
+				prices.drain(..excess);
+			}
+			Ok(prices)
+		});
+		if let Ok(Err(_)) = result {
+			debug::warn!("Prices were updated concurrently, dropping {}", price);
+		}
+	}
+}
+
+/// Extract a positive price at `pointer` from a JSON response.
+pub fn parse_price(body: &[u8], pointer: &[u8]) -> Option<Price> {
+	json::parse(body)?
+		.pointer(pointer)?
+		.as_fixed_point(PRICE_DECIMALS)
+		.filter(|price| *price > 0)
+}
+
+/// Combine weighted prices as configured, None if there are none or all were rejected.
+pub fn aggregate(prices: &[(Price, u32)], config: &AggregationConfig) -> Option<Price> {
+	let mut prices = prices.to_vec();
+	prices.sort_by_key(|(price, _)| *price);
+
+	if let Some(max_deviation) = config.max_deviation {
+		let median = weighted_median(&prices)?;
+		let max_distance = max_deviation * median;
+		prices.retain(|(price, _)| price.max(&median) - price.min(&median) <= max_distance);
+	}
+
+	match config.method {
+		AggregationMethod::Mean => weighted_average(&prices),
+		AggregationMethod::Median => weighted_median(&prices),
+		AggregationMethod::TrimmedMean(trim) => trimmed_mean(&prices, trim),
+	}
+}
+
+/// The lowest of the prices, sorted ascending, with at least half of the weight at or below it.
+pub fn weighted_median(prices: &[(Price, u32)]) -> Option<Price> {
+	let total_weight = prices.iter().map(|(_, weight)| *weight as u64).sum::<u64>();
+	let mut weight_below = 0;
+	prices.iter()
+		.find(|(_, weight)| {
+			weight_below += *weight as u64;
+			weight_below * 2 >= total_weight && weight_below > 0
+		})
+		.map(|(price, _)| *price)
+}
+
+/// The weighted mean of the prices, sorted ascending, once `trim` of the total weight is dropped
+/// at each end. An entry straddling a cut keeps only the weight inside it.
+pub fn trimmed_mean(prices: &[(Price, u32)], trim: Percent) -> Option<Price> {
+	let total_weight = prices.iter().map(|(_, weight)| *weight as u64).sum::<u64>();
+	let cut = (total_weight * trim.deconstruct() as u64 / 100).min(total_weight / 2);
+	let (low, high) = (cut, total_weight - cut);
+	let mut weight_below = 0;
+	let kept = prices.iter()
+		.filter_map(|(price, weight)| {
+			let (start, end) = (weight_below, weight_below + *weight as u64);
+			weight_below = end;
+			let inside = end.min(high).saturating_sub(start.max(low));
+			if inside > 0 { Some((*price, inside as u32)) } else { None }
+		})
+		.collect::<Vec<_>>();
+	weighted_average(&kept)
+}
+
+/// The mean of the prices weighted by their weights, None if there are none.
+pub fn weighted_average(prices: &[(Price, u32)]) -> Option<Price> {
+	let total_weight = prices.iter().map(|(_, weight)| *weight as u128).sum::<u128>();
+	if total_weight == 0 {
+		return None;
+	}
+	let sum = prices.iter().map(|(price, weight)| *price as u128 * *weight as u128).sum::<u128>();
+	Some((sum / total_weight) as Price)
+}
+
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	/// Accept a signed price payload of an oracle authority that has not submitted one in
+	/// the last `UnsignedInterval` blocks. The payload itself expires `UnsignedInterval` blocks
+	/// after the block it was signed at, so an old price cannot be replayed as a fresh one.
+	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+		let (payload, signature) = match call {
+			Call::submit_price_unsigned_with_signed_payload(payload, signature) => (payload, signature),
+			_ => return InvalidTransaction::Call.into(),
+		};
+
+		if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+			return InvalidTransaction::BadProof.into();
+		}
+
+		let authority = payload.public.clone().into_account();
+		if !Self::oracle_authorities().contains(&authority) {
+			return InvalidTransaction::BadProof.into();
+		}
+
+		let now = system::Module::<T>::block_number();
+		if payload.block_number > now {
+			return InvalidTransaction::Future.into();
+		}
+		if payload.block_number.saturating_add(T::UnsignedInterval::get()) < now {
+			return InvalidTransaction::Stale.into();
+		}
+		let next_unsigned_at = Self::next_unsigned_at(&authority);
+		if payload.block_number < next_unsigned_at {
+			return InvalidTransaction::Stale.into();
+		}
+
+		ValidTransaction::with_tag_prefix("TemplateOracle")
+			.priority(T::UnsignedPriority::get())
+			// One submission per authority and interval
+			.and_provides((authority, next_unsigned_at))
+			.longevity(T::UnsignedInterval::get().unique_saturated_into())
+			.propagate(true)
+			.build()
+	}
+}
 
+impl<T: Trait> PriceFeed for Module<T> {
+	fn eth_price() -> Option<Price> {
+		Self::current_price()
 	}
 }
@@ -1,10 +1,11 @@
 // Creating mock runtime here
 
-use crate::{Module, Trait};
-use sp_core::H256;
+use crate::{Module, Trait, Call, GenesisConfig};
+use sp_core::{H256, sr25519::Signature};
 use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+	traits::{BlakeTwo256, IdentityLookup, IdentifyAccount, Verify},
+	testing::{Header, TestXt}, Perbill,
 };
 use frame_system as system;
 
@@ -30,7 +31,7 @@ impl system::Trait for Test {
 	type BlockNumber = u64;
 	type Hash = H256;
 	type Hashing = BlakeTwo256;
-	type AccountId = u64;
+	type AccountId = AccountId;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
 	type Event = ();
@@ -48,13 +49,66 @@ impl system::Trait for Test {
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 }
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+}
+
+pub type Extrinsic = TestXt<Call<Test>, ()>;
+pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+impl system::offchain::SigningTypes for Test {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<LocalCall> system::offchain::SendTransactionTypes<LocalCall> for Test where
+	Call<Test>: From<LocalCall>,
+{
+	type OverarchingCall = Call<Test>;
+	type Extrinsic = Extrinsic;
+}
+
+/// The genesis providers, as (url, price pointer).
+pub const COINCAP: (&str, &str) = ("https://api.coincap.io/v2/assets/ethereum", "/data/priceUsd");
+pub const CRYPTOCOMPARE: (&str, &str) = ("https://min-api.cryptocompare.com/data/price?fsym=ETH&tsyms=USD", "/USD");
+pub const COINGECKO: (&str, &str) = ("https://api.coingecko.com/api/v3/simple/price?ids=ethereum&vs_currencies=usd", "/ethereum/usd");
+
+parameter_types! {
+	pub const MaxProviders: u32 = 4;
+	pub const MaxProviderFieldLength: u32 = 128;
+	pub const MaxPrices: u32 = 2;
+	pub const MaxPriceAge: u64 = 1000;
+	pub const UnsignedInterval: u64 = 3;
+	pub const UnsignedPriority: u64 = 100;
+}
 impl Trait for Test {
+	type AuthorityId = crate::crypto::AuthId;
 	type Event = ();
+	type Time = Timestamp;
+	type MaxPriceAge = MaxPriceAge;
+	type MaxProviders = MaxProviders;
+	type MaxProviderFieldLength = MaxProviderFieldLength;
+	type MaxPrices = MaxPrices;
+	type UnsignedInterval = UnsignedInterval;
+	type UnsignedPriority = UnsignedPriority;
 }
 pub type TemplateModule = Module<Test>;
+pub type Timestamp = pallet_timestamp::Module<Test>;
 
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig::<Test> {
+		oracle_authorities: vec![],
+		providers: [COINCAP, CRYPTOCOMPARE, COINGECKO].iter()
+			.map(|(url, price_pointer)| (url.as_bytes().to_vec(), price_pointer.as_bytes().to_vec(), 1))
+			.collect(),
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
 }
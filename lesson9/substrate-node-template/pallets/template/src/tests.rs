@@ -1,18 +1,506 @@
 // Tests to be written here
 
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use crate::{
+	aggregate, json, parse_price, trimmed_mean, weighted_average, weighted_median, mock::*, AggregationConfig, AggregationMethod,
+	Call, Error, Price, PriceFeed, PricePayload, Provider, KEY_TYPE,
+};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::OnFinalize, unsigned::ValidateUnsigned};
+use frame_system::{self as system, RawOrigin};
+use sp_core::{
+	offchain::{OffchainExt, TransactionPoolExt, testing},
+	sr25519, testing::KeyStore, traits::KeystoreExt,
+};
+use sp_runtime::{
+	DispatchError, Percent, Permill,
+	transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidityError},
+};
+
+const COINCAP_RESPONSE: &[u8] = br#"{"data":{"id":"ethereum","symbol":"ETH","priceUsd":"2345.6789"},"timestamp":1593000000000}"#;
+const CRYPTOCOMPARE_RESPONSE: &[u8] = br#"{"USD":2350.12}"#;
+const COINGECKO_RESPONSE: &[u8] = br#"{"ethereum":{"usd":2340.2}}"#;
+
+fn expect_responses(state: &mut testing::OffchainState, responses: &[(&str, &[u8])]) {
+	for (id, (url, response)) in responses.iter().enumerate() {
+		state.expect_request(id as u16, testing::PendingRequest {
+			method: "GET".into(),
+			uri: (*url).into(),
+			response: Some(response.to_vec()),
+			sent: true,
+			..Default::default()
+		});
+	}
+}
+
+#[test]
+fn json_parses_nested_documents() {
+	let value = json::parse(br#" {"a": [1, -2.5e1, "x\"\u00e9\ud83d\ude00"], "b/c": {"~d": true}, "e": null} "#).unwrap();
+
+	assert_eq!(value.pointer(b"/a/0"), Some(&json::JsonValue::Number(b"1".to_vec())));
+	assert_eq!(value.pointer(b"/a/2"), Some(&json::JsonValue::String("x\"é😀".as_bytes().to_vec())));
+	assert_eq!(value.pointer(b"/b~1c/~0d"), Some(&json::JsonValue::Bool(true)));
+	assert_eq!(value.pointer(b"/e"), Some(&json::JsonValue::Null));
+	assert_eq!(value.pointer(b"/a/3"), None);
+	assert_eq!(value.pointer(b"/f"), None);
+}
+
+#[test]
+fn json_rejects_malformed_documents() {
+	for document in &[&b"{"[..], b"[1,]", b"{\"a\" 1}", b"01", b"1.", b"\"\x01\"", b"[1] 2", b"tru"] {
+		assert_eq!(json::parse(document), None);
+	}
+	assert_eq!(json::parse(&[b'['; 64][..]), None);
+}
+
+#[test]
+fn json_numbers_convert_to_fixed_point() {
+	assert_eq!(json::parse_fixed_point(b"2345.6789", 2), Some(234567));
+	assert_eq!(json::parse_fixed_point(b"2345.6", 2), Some(234560));
+	assert_eq!(json::parse_fixed_point(b"2.3402e3", 2), Some(234020));
+	assert_eq!(json::parse_fixed_point(b"15e-1", 2), Some(150));
+	assert_eq!(json::parse_fixed_point(b"0.001", 2), Some(0));
+	assert_eq!(json::parse_fixed_point(b"-1", 2), None);
+	assert_eq!(json::parse_fixed_point(b"99999999999999999999", 2), None);
+	assert_eq!(json::parse_fixed_point(b"abc", 2), None);
+}
+
+#[test]
+fn parse_price_supports_provider_shapes() {
+	assert_eq!(parse_price(COINCAP_RESPONSE, COINCAP.1.as_bytes()), Some(234567));
+	assert_eq!(parse_price(CRYPTOCOMPARE_RESPONSE, CRYPTOCOMPARE.1.as_bytes()), Some(235012));
+	assert_eq!(parse_price(COINGECKO_RESPONSE, COINGECKO.1.as_bytes()), Some(234020));
+	assert_eq!(parse_price(br#"{"USD":0}"#, CRYPTOCOMPARE.1.as_bytes()), None);
+	assert_eq!(parse_price(COINCAP_RESPONSE, CRYPTOCOMPARE.1.as_bytes()), None);
+}
+
+#[test]
+fn weighted_average_of_prices() {
+	assert_eq!(weighted_average(&[]), None);
+	assert_eq!(weighted_average(&[(100, 1), (201, 1)]), Some(150));
+	assert_eq!(weighted_average(&[(100, 3), (200, 1)]), Some(125));
+}
+
+#[test]
+fn aggregation_methods() {
+	let prices = [(100, 1), (104, 1), (110, 2), (200, 1)];
+	let config = |method, max_deviation| AggregationConfig { method, max_deviation };
+
+	assert_eq!(aggregate(&prices, &config(AggregationMethod::Mean, None)), Some(124));
+	assert_eq!(aggregate(&prices, &config(AggregationMethod::Median, None)), Some(110));
+	assert_eq!(aggregate(&prices, &config(AggregationMethod::TrimmedMean(Percent::from_percent(25)), None)), Some(108));
+	assert_eq!(aggregate(&[], &config(AggregationMethod::Median, None)), None);
+	assert_eq!(aggregate(&[(100, 1)], &config(AggregationMethod::TrimmedMean(Percent::from_percent(49)), None)), Some(100));
+
+	assert_eq!(trimmed_mean(&[(100, 1), (110, 8), (300, 1)], Percent::from_percent(10)), Some(110));
+	assert_eq!(trimmed_mean(&[(100, 2), (110, 2)], Percent::from_percent(25)), Some(105));
+	assert_eq!(trimmed_mean(&[(100, 1), (200, 3)], Percent::from_percent(25)), Some(200));
+	assert_eq!(trimmed_mean(&[], Percent::from_percent(25)), None);
+
+	assert_eq!(weighted_median(&[(100, 1), (201, 1)]), Some(100));
+	assert_eq!(weighted_median(&[(100, 1), (201, 2)]), Some(201));
+}
+
+#[test]
+fn aggregation_rejects_outliers() {
+	let prices = [(100, 1), (104, 1), (110, 2), (200, 1), (1, 1)];
+	let config = |max_deviation| AggregationConfig { method: AggregationMethod::Mean, max_deviation };
+
+	// Only the prices within 10% of the median 104 are kept
+	assert_eq!(aggregate(&prices, &config(Some(Permill::from_percent(10)))), Some((100 + 104 + 110 * 2) / 4));
+	assert_eq!(aggregate(&prices, &config(Some(Permill::zero()))), Some(104));
+	assert_eq!(aggregate(&prices, &config(None)), Some((100 + 104 + 110 * 2 + 200 + 1) / 6));
+}
+
+#[test]
+fn offchain_worker_aggregates_providers() {
+	let (offchain, state) = testing::TestOffchainExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+
+	expect_responses(&mut state.write(), &[
+		(COINCAP.0, COINCAP_RESPONSE),
+		(CRYPTOCOMPARE.0, CRYPTOCOMPARE_RESPONSE),
+		(COINGECKO.0, COINGECKO_RESPONSE),
+	]);
+
+	t.execute_with(|| {
+		// The median by default
+		assert_eq!(TemplateModule::fetch_price(), Ok(234567));
+	});
+}
+
+#[test]
+fn offchain_worker_discards_failed_providers() {
+	let (offchain, state) = testing::TestOffchainExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+
+	expect_responses(&mut state.write(), &[
+		(COINCAP.0, b"Service Unavailable"),
+		(CRYPTOCOMPARE.0, CRYPTOCOMPARE_RESPONSE),
+		(COINGECKO.0, br#"{"data":{}}"#),
+	]);
+
+	t.execute_with(|| {
+		assert_eq!(TemplateModule::fetch_price(), Ok(235012));
+	});
+}
+
+#[test]
+fn offchain_worker_discards_oversized_responses() {
+	let (offchain, state) = testing::TestOffchainExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+
+	let mut oversized = vec![b' '; 16 * 1024];
+	oversized.extend_from_slice(COINCAP_RESPONSE);
+	expect_responses(&mut state.write(), &[
+		(COINCAP.0, &oversized[..]),
+		(CRYPTOCOMPARE.0, CRYPTOCOMPARE_RESPONSE),
+		(COINGECKO.0, br#"{}"#),
+	]);
+
+	t.execute_with(|| {
+		assert_eq!(TemplateModule::fetch_price(), Ok(235012));
+	});
+}
+
+#[test]
+fn offchain_worker_fails_without_prices() {
+	let (offchain, state) = testing::TestOffchainExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+
+	expect_responses(&mut state.write(), &[(COINCAP.0, b""), (CRYPTOCOMPARE.0, b"{}"), (COINGECKO.0, b"[]")]);
+
+	t.execute_with(|| {
+		assert!(TemplateModule::fetch_price().is_err());
+	});
+}
+
+fn provider((url, price_pointer): (&str, &str), weight: u32, enabled: bool) -> Provider {
+	Provider { url: url.as_bytes().to_vec(), price_pointer: price_pointer.as_bytes().to_vec(), weight, enabled }
+}
+
+#[test]
+fn offchain_worker_reads_the_provider_registry() {
+	let (offchain, state) = testing::TestOffchainExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+
+	// Only the enabled providers are queried, in registration order
+	expect_responses(&mut state.write(), &[
+		(COINCAP.0, COINCAP_RESPONSE),
+		(COINGECKO.0, COINGECKO_RESPONSE),
+	]);
+
+	t.execute_with(|| {
+		assert_ok!(TemplateModule::update_provider(RawOrigin::Root.into(), 0, provider(COINCAP, 3, true)));
+		assert_ok!(TemplateModule::update_provider(RawOrigin::Root.into(), 1, provider(CRYPTOCOMPARE, 1, false)));
+
+		let mean = AggregationConfig { method: AggregationMethod::Mean, max_deviation: None };
+		assert_ok!(TemplateModule::set_aggregation(RawOrigin::Root.into(), mean));
+
+		assert_eq!(TemplateModule::fetch_price(), Ok((234567 * 3 + 234020) / 4));
+	});
+}
 
 #[test]
-fn test_onchain() {
+fn offchain_worker_fails_without_providers() {
 	new_test_ext().execute_with(|| {
-		// Test onchain logic here
+		for id in 0..3 {
+			assert_ok!(TemplateModule::remove_provider(RawOrigin::Root.into(), id));
+		}
+
+		assert_eq!(TemplateModule::fetch_price(), Err("No provider is enabled"));
+	});
+}
+
+#[test]
+fn providers_are_managed_by_root() {
+	new_test_ext().execute_with(|| {
+		let kraken = ("https://api.kraken.com/0/public/Ticker?pair=ETHUSD", "/result/XETHZUSD/c/0");
+		assert_eq!(TemplateModule::provider_count(), 3);
+		assert!(TemplateModule::add_provider(Origin::signed(sr25519::Public::from_raw([1; 32])), provider(kraken, 1, true)).is_err());
+
+		assert_ok!(TemplateModule::add_provider(RawOrigin::Root.into(), provider(kraken, 1, true)));
+		assert_eq!(TemplateModule::providers(3), Some(provider(kraken, 1, true)));
+		assert_eq!(TemplateModule::provider_count(), 4);
+		assert_noop!(
+			TemplateModule::add_provider(RawOrigin::Root.into(), provider(kraken, 1, true)),
+			Error::<Test>::TooManyProviders
+		);
+
+		assert_ok!(TemplateModule::remove_provider(RawOrigin::Root.into(), 0));
+		assert_eq!(TemplateModule::providers(0), None);
+		assert_eq!(TemplateModule::provider_count(), 3);
+		assert_noop!(TemplateModule::remove_provider(RawOrigin::Root.into(), 0), Error::<Test>::ProviderNotExist);
+		assert_noop!(
+			TemplateModule::update_provider(RawOrigin::Root.into(), 0, provider(kraken, 1, true)),
+			Error::<Test>::ProviderNotExist
+		);
+
+		// Ids are not reused
+		assert_ok!(TemplateModule::add_provider(RawOrigin::Root.into(), provider(COINCAP, 1, true)));
+		assert_eq!(TemplateModule::providers(4), Some(provider(COINCAP, 1, true)));
 	});
 }
 
 #[test]
-fn test_offchain() {
+fn providers_are_validated() {
 	new_test_ext().execute_with(|| {
-		// Test offchain worker here
+		let long_url = format!("https://example.com/{}", "a".repeat(128));
+		for (config, error) in vec![
+			(provider(("ftp://example.com", "/USD"), 1, true), Error::<Test>::InvalidProviderUrl),
+			(provider((&long_url, "/USD"), 1, true), Error::<Test>::InvalidProviderUrl),
+			(provider(("https://example.com", "USD"), 1, true), Error::<Test>::InvalidPricePointer),
+			(provider(("https://example.com", "/USD"), 0, true), Error::<Test>::ZeroProviderWeight),
+		] {
+			let error = DispatchError::from(error);
+			assert_noop!(TemplateModule::add_provider(RawOrigin::Root.into(), config.clone()), error.clone());
+			assert_noop!(TemplateModule::update_provider(RawOrigin::Root.into(), 0, config), error);
+		}
+
+		let invalid_utf8 = Provider { url: b"https://\xff".to_vec(), ..provider(COINCAP, 1, true) };
+		assert_noop!(
+			TemplateModule::add_provider(RawOrigin::Root.into(), invalid_utf8),
+			Error::<Test>::InvalidProviderUrl
+		);
+	});
+}
+
+#[test]
+fn prices_are_bounded() {
+	let (offchain, _state) = testing::TestOffchainExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+
+	t.execute_with(|| {
+		TemplateModule::append_price(1);
+		TemplateModule::append_price(2);
+		TemplateModule::append_price(3);
+
+		assert_eq!(TemplateModule::offchain_prices(), vec![2, 3]);
+	});
+}
+
+fn onchain_prices() -> Vec<Price> {
+	TemplateModule::prices().iter().map(|p| p.price).collect()
+}
+
+/// Submit a price from the offchain worker and return the unsigned transaction's payload and signature.
+fn submit_unsigned(
+	pool_state: &mut testing::PoolState,
+	block_number: u64,
+	price: u64,
+) -> (PricePayload<sr25519::Public, u64>, sr25519::Signature) {
+	TemplateModule::submit_price_unsigned(block_number, price).unwrap();
+
+	let tx = pool_state.transactions.pop().unwrap();
+	let tx = Extrinsic::decode(&mut &*tx).unwrap();
+	assert!(tx.signature.is_none());
+	match tx.call {
+		Call::submit_price_unsigned_with_signed_payload(payload, signature) => (payload, signature),
+		call => panic!("unexpected call {:?}", call),
+	}
+}
+
+fn validate(
+	payload: &PricePayload<sr25519::Public, u64>,
+	signature: &sr25519::Signature,
+) -> Result<(), TransactionValidityError> {
+	let call = Call::submit_price_unsigned_with_signed_payload(payload.clone(), signature.clone());
+	TemplateModule::validate_unsigned(TransactionSource::External, &call).map(|_| ())
+}
+
+#[test]
+fn unsigned_prices_are_rate_limited() {
+	let (offchain, _state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	let authority = keystore.write().sr25519_generate_new(KEY_TYPE, Some("//Alice")).unwrap();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(keystore));
+
+	t.execute_with(|| {
+		system::Module::<Test>::set_block_number(1);
+		assert_ok!(TemplateModule::add_oracle_authority(RawOrigin::Root.into(), authority));
+
+		let (payload, signature) = submit_unsigned(&mut pool_state.write(), 1, 234567);
+		assert_eq!(payload, PricePayload { price: 234567, block_number: 1, public: authority });
+
+		let call = Call::submit_price_unsigned_with_signed_payload(payload.clone(), signature.clone());
+		let valid = TemplateModule::validate_unsigned(TransactionSource::External, &call).unwrap();
+		assert_eq!(valid.priority, 100);
+		assert_eq!(valid.longevity, 3);
+		assert!(valid.propagate);
+
+		assert_ok!(TemplateModule::submit_price_unsigned_with_signed_payload(RawOrigin::None.into(), payload.clone(), signature.clone()));
+		assert_eq!(onchain_prices(), vec![234567]);
+		assert_eq!(TemplateModule::next_unsigned_at(authority), 4);
+
+		// Replaying the payload, or submitting again within the interval, is rejected
+		assert_eq!(validate(&payload, &signature), Err(InvalidTransaction::Stale.into()));
+		system::Module::<Test>::set_block_number(3);
+		let (payload, signature) = submit_unsigned(&mut pool_state.write(), 3, 235012);
+		assert_eq!(validate(&payload, &signature), Err(InvalidTransaction::Stale.into()));
+
+		system::Module::<Test>::set_block_number(4);
+		let (payload, signature) = submit_unsigned(&mut pool_state.write(), 4, 235012);
+		assert_eq!(validate(&payload, &signature), Ok(()));
+
+		// Payloads from the future are not accepted yet
+		let (payload, signature) = submit_unsigned(&mut pool_state.write(), 5, 235012);
+		assert_eq!(validate(&payload, &signature), Err(InvalidTransaction::Future.into()));
+
+		// Payloads older than the interval are not accepted anymore
+		system::Module::<Test>::set_block_number(7);
+		let (payload, signature) = submit_unsigned(&mut pool_state.write(), 4, 235012);
+		assert_eq!(validate(&payload, &signature), Ok(()));
+		system::Module::<Test>::set_block_number(8);
+		assert_eq!(validate(&payload, &signature), Err(InvalidTransaction::Stale.into()));
+	});
+}
+
+#[test]
+fn unsigned_prices_stay_rate_limited_when_an_authority_is_added_again() {
+	let (offchain, _state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	let authority = keystore.write().sr25519_generate_new(KEY_TYPE, Some("//Alice")).unwrap();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(keystore));
+
+	t.execute_with(|| {
+		system::Module::<Test>::set_block_number(1);
+		assert_ok!(TemplateModule::add_oracle_authority(RawOrigin::Root.into(), authority));
+		let (payload, signature) = submit_unsigned(&mut pool_state.write(), 1, 234567);
+		assert_ok!(TemplateModule::submit_price_unsigned_with_signed_payload(RawOrigin::None.into(), payload.clone(), signature.clone()));
+
+		assert_ok!(TemplateModule::remove_oracle_authority(RawOrigin::Root.into(), authority));
+		assert_ok!(TemplateModule::add_oracle_authority(RawOrigin::Root.into(), authority));
+
+		assert_eq!(TemplateModule::next_unsigned_at(authority), 4);
+		assert_eq!(validate(&payload, &signature), Err(InvalidTransaction::Stale.into()));
+	});
+}
+
+#[test]
+fn unsigned_prices_require_an_authority_signature() {
+	let (offchain, _state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	let authority = keystore.write().sr25519_generate_new(KEY_TYPE, Some("//Alice")).unwrap();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(keystore));
+
+	t.execute_with(|| {
+		system::Module::<Test>::set_block_number(1);
+		let (payload, signature) = submit_unsigned(&mut pool_state.write(), 1, 234567);
+
+		// Not whitelisted
+		assert_eq!(validate(&payload, &signature), Err(InvalidTransaction::BadProof.into()));
+
+		assert_ok!(TemplateModule::add_oracle_authority(RawOrigin::Root.into(), authority));
+		assert_eq!(validate(&payload, &signature), Ok(()));
+
+		// Tampered with
+		let forged = PricePayload { price: 1, ..payload.clone() };
+		assert_eq!(validate(&forged, &signature), Err(InvalidTransaction::BadProof.into()));
+
+		assert_ok!(TemplateModule::remove_oracle_authority(RawOrigin::Root.into(), authority));
+		assert_eq!(validate(&payload, &signature), Err(InvalidTransaction::BadProof.into()));
+	});
+}
+
+#[test]
+fn oracle_authorities_are_managed_by_root() {
+	new_test_ext().execute_with(|| {
+		let authority = sr25519::Public::from_raw([1; 32]);
+
+		assert_noop!(
+			TemplateModule::submit_price(Origin::signed(authority), 234567),
+			Error::<Test>::NotOracleAuthority
+		);
+		assert!(TemplateModule::add_oracle_authority(Origin::signed(authority), authority).is_err());
+
+		assert_ok!(TemplateModule::add_oracle_authority(RawOrigin::Root.into(), authority));
+		assert_noop!(
+			TemplateModule::add_oracle_authority(RawOrigin::Root.into(), authority),
+			Error::<Test>::AlreadyOracleAuthority
+		);
+
+		assert_ok!(TemplateModule::submit_price(Origin::signed(authority), 234567));
+		assert_ok!(TemplateModule::submit_price(Origin::signed(authority), 235012));
+		assert_ok!(TemplateModule::submit_price(Origin::signed(authority), 234020));
+		assert_eq!(onchain_prices(), vec![235012, 234020]);
+
+		assert_ok!(TemplateModule::remove_oracle_authority(RawOrigin::Root.into(), authority));
+		assert_noop!(
+			TemplateModule::remove_oracle_authority(RawOrigin::Root.into(), authority),
+			Error::<Test>::NotOracleAuthority
+		);
+	});
+}
+
+#[test]
+fn aggregation_is_managed_by_root() {
+	new_test_ext().execute_with(|| {
+		let authority = sr25519::Public::from_raw([1; 32]);
+		let trimmed = |percent| AggregationConfig {
+			method: AggregationMethod::TrimmedMean(Percent::from_percent(percent)),
+			max_deviation: Some(Permill::from_percent(5)),
+		};
+		assert_eq!(TemplateModule::aggregation(), AggregationConfig::default());
+
+		assert!(TemplateModule::set_aggregation(Origin::signed(authority), trimmed(20)).is_err());
+		assert_noop!(
+			TemplateModule::set_aggregation(RawOrigin::Root.into(), trimmed(50)),
+			Error::<Test>::InvalidAggregation
+		);
+
+		assert_ok!(TemplateModule::set_aggregation(RawOrigin::Root.into(), trimmed(20)));
+		assert_eq!(TemplateModule::aggregation(), trimmed(20));
+	});
+}
+
+#[test]
+fn stale_prices_are_not_exposed() {
+	new_test_ext().execute_with(|| {
+		let authority = sr25519::Public::from_raw([1; 32]);
+		assert_ok!(TemplateModule::add_oracle_authority(RawOrigin::Root.into(), authority));
+
+		Timestamp::set_timestamp(1000);
+		assert_ok!(TemplateModule::submit_price(Origin::signed(authority), 234567));
+		assert_eq!(TemplateModule::prices()[0].timestamp, 1000);
+		Timestamp::set_timestamp(1500);
+		assert_ok!(TemplateModule::submit_price(Origin::signed(authority), 235012));
+
+		// The lower median of both prices
+		assert_eq!(TemplateModule::eth_price(), Some(234567));
+
+		// Only the second price is young enough
+		Timestamp::set_timestamp(2200);
+		assert_eq!(TemplateModule::eth_price(), Some(235012));
+		TemplateModule::on_finalize(1);
+		assert!(!TemplateModule::is_stale());
+
+		Timestamp::set_timestamp(2501);
+		assert_eq!(TemplateModule::eth_price(), None);
+		TemplateModule::on_finalize(2);
+		assert!(TemplateModule::is_stale());
+
+		assert_ok!(TemplateModule::submit_price(Origin::signed(authority), 234020));
+		assert!(!TemplateModule::is_stale());
+		assert_eq!(TemplateModule::eth_price(), Some(234020));
 	});
 }
@@ -12,7 +12,7 @@ use sp_std::prelude::*;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
-	transaction_validity::{TransactionValidity, TransactionSource},
+	transaction_validity::{TransactionValidity, TransactionSource, TransactionPriority},
 };
 use sp_runtime::traits::{
 	BlakeTwo256, Block as BlockT, IdentityLookup, Verify, IdentifyAccount, NumberFor, Saturating,
@@ -252,9 +252,38 @@ impl sudo::Trait for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const MaxOracleProviders: u32 = 16;
+	pub const MaxOracleProviderFieldLength: u32 = 256;
+	pub const MaxEthPrices: u32 = 100;
+	pub const MaxEthPriceAge: u64 = 10 * MINUTES as u64 * MILLISECS_PER_BLOCK;
+	pub const OracleUnsignedInterval: BlockNumber = 10;
+	pub const OracleUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+}
+
 /// Used for the module template in `./template.rs`
 impl template::Trait for Runtime {
+	type AuthorityId = template::crypto::AuthId;
 	type Event = Event;
+	type Time = Timestamp;
+	type MaxPriceAge = MaxEthPriceAge;
+	type MaxProviders = MaxOracleProviders;
+	type MaxProviderFieldLength = MaxOracleProviderFieldLength;
+	type MaxPrices = MaxEthPrices;
+	type UnsignedInterval = OracleUnsignedInterval;
+	type UnsignedPriority = OracleUnsignedPriority;
+}
+
+impl system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> system::offchain::SendTransactionTypes<C> for Runtime where
+	Call: From<C>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = UncheckedExtrinsic;
 }
 
 construct_runtime!(
@@ -272,7 +301,7 @@ construct_runtime!(
 		TransactionPayment: transaction_payment::{Module, Storage},
 		Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Used for the module template in `./template.rs`
-		TemplateModule: template::{Module, Call, Storage, Event<T>},
+		TemplateModule: template::{Module, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
 	}
 );
 